@@ -0,0 +1,85 @@
+//! Times building the Day 14 disk grid, which takes 128 knot hashes, against a reimplementation of
+//! the original knot hash that cloned the whole list for every length.
+//!
+//! Run with `cargo bench`.
+
+extern crate advent_of_code_2017;
+
+use advent_of_code_2017::util::knot_hash::KnotHash;
+use advent_of_code_2017::{Day, Day14};
+
+use std::time::{Duration, Instant};
+
+const KEY: &str = "flqrgnkx";
+const ITERATIONS: u32 = 20;
+
+/// The knot hash as it was first written: every length copies the list into a new `Vec`.
+fn cloning_knot_hash(input: &str) -> [u8; 16] {
+    let mut lengths: Vec<usize> = input.bytes().map(|byte| byte as usize).collect();
+    lengths.extend_from_slice(&[17, 31, 73, 47, 23]);
+
+    let mut list: Vec<u32> = (0..256).collect();
+    let mut offset = 0;
+    let mut skip_size = 0;
+
+    for _ in 0..64 {
+        for &length in lengths.iter() {
+            let mut new_list = list.clone();
+            for i in 0..length {
+                new_list[(offset + i) % 256] = list[(offset + length - i - 1) % 256];
+            }
+            list = new_list;
+            offset = (offset + length + skip_size) % 256;
+            skip_size += 1;
+        }
+    }
+
+    let mut bytes = [0; 16];
+    for (byte, block) in bytes.iter_mut().zip(list.chunks(16)) {
+        *byte = block.iter().fold(0, |acc, &val| acc ^ val) as u8;
+    }
+    bytes
+}
+
+fn time<F: FnMut()>(name: &str, mut f: F) -> Duration {
+    // Warm up once before timing.
+    f();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let per_iteration = start.elapsed() / ITERATIONS;
+
+    println!("{:<32} {:>10.3} ms/iter", name, per_iteration.as_secs_f64() * 1000.0);
+    per_iteration
+}
+
+fn main() {
+    let rows: Vec<String> = (0..128).map(|row| format!("{}-{}", KEY, row)).collect();
+
+    for row in rows.iter() {
+        let expected = cloning_knot_hash(row);
+        let hash = KnotHash::hash_str(row);
+        assert!((0..16).all(|i| hash[i as u8] == expected[i]));
+    }
+
+    let cloning = time("grid hashes (cloning)", || {
+        for row in rows.iter() {
+            std::hint::black_box(cloning_knot_hash(row));
+        }
+    });
+    let in_place = time("grid hashes (in place)", || {
+        for row in rows.iter() {
+            std::hint::black_box(KnotHash::hash_str(row));
+        }
+    });
+    time("Day14::from_str", || {
+        std::hint::black_box(Day14::from_str(KEY));
+    });
+
+    println!(
+        "In place knot hash is {:.1}x faster",
+        cloning.as_secs_f64() / in_place.as_secs_f64()
+    );
+}
@@ -0,0 +1,131 @@
+use std::fmt;
+
+/// An inverse captcha: a circular list of digits that is solved by summing every digit that
+/// matches the digit `offset` places further around the list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Captcha<'a> {
+    digits: &'a [u8],
+}
+
+/// A byte in a captcha that is not an ASCII digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptchaError {
+    pub index: usize,
+    pub byte: u8,
+}
+
+impl fmt::Display for CaptchaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid captcha digit {:?} at index {}",
+            self.byte as char,
+            self.index
+        )
+    }
+}
+
+impl<'a> Captcha<'a> {
+    /// Wrap a slice of ASCII digits, such as the bytes of the puzzle input.
+    pub fn new(digits: &'a [u8]) -> Result<Self, CaptchaError> {
+        match digits.iter().position(|byte| !byte.is_ascii_digit()) {
+            Some(index) => Err(CaptchaError {
+                index,
+                byte: digits[index],
+            }),
+            None => Ok(Captcha { digits }),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.digits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// The value of the digit at `index`.
+    pub fn digit(&self, index: usize) -> u32 {
+        u32::from(self.digits[index] - b'0')
+    }
+
+    /// The pairs of indices `(i, j)` where `j` is `offset` places after `i`, wrapping around the
+    /// end of the list, and both hold the same digit.
+    fn matches(&self, offset: usize) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let digits = self.digits;
+        let len = digits.len();
+        let offset = if len == 0 { 0 } else { offset % len };
+
+        (0..len)
+            .map(move |i| (i, (i + offset) % len))
+            .filter(move |&(i, j)| digits[i] == digits[j])
+    }
+
+    /// Solve the captcha by comparing each digit with the one `offset` places after it.
+    pub fn sum(&self, offset: usize) -> u32 {
+        self.matches(offset).map(|(i, _)| self.digit(i)).sum()
+    }
+
+    /// Every matching pair of indices that contributes to `sum(offset)`.
+    pub fn matching_pairs(&self, offset: usize) -> Vec<(usize, usize)> {
+        self.matches(offset).collect()
+    }
+
+    /// The sum comparing each digit with the next one.
+    pub fn next_digit_sum(&self) -> u32 {
+        self.sum(1)
+    }
+
+    /// The sum comparing each digit with the one halfway around the list.
+    pub fn halfway_sum(&self) -> u32 {
+        self.sum(self.len() / 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arbitrary_offsets() {
+        let captcha = Captcha::new(b"1231").unwrap();
+
+        assert_eq!(7, captcha.sum(0));
+        assert_eq!(1, captcha.sum(1));
+        assert_eq!(0, captcha.sum(2));
+        assert_eq!(1, captcha.sum(3));
+        assert_eq!(1, captcha.sum(5));
+    }
+
+    #[test]
+    fn matching_pairs() {
+        let captcha = Captcha::new(b"91212129").unwrap();
+
+        assert_eq!(vec![(7, 0)], captcha.matching_pairs(1));
+        assert_eq!(
+            vec![(1, 3), (2, 4), (3, 5), (4, 6)],
+            captcha.matching_pairs(2)
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let captcha = Captcha::new(b"").unwrap();
+
+        assert_eq!(0, captcha.next_digit_sum());
+        assert_eq!(0, captcha.halfway_sum());
+        assert!(captcha.matching_pairs(3).is_empty());
+    }
+
+    #[test]
+    fn invalid_digit() {
+        assert_eq!(
+            Err(CaptchaError {
+                index: 2,
+                byte: b'\n',
+            }),
+            Captcha::new(b"12\n")
+        );
+    }
+}
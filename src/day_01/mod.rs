@@ -1,44 +1,31 @@
 use super::Day;
 
+mod captcha;
+
+pub use self::captcha::{Captcha, CaptchaError};
+
 /// [Day 1](htts://adventofcode.com/2017/day/1). Calculate the solution to a captcha string.
-pub struct Day01 {
-    numbers: Vec<u32>,
+pub struct Day01<'a> {
+    captcha: Captcha<'a>,
 }
 
-impl<'a> Day<'a> for Day01 {
+impl<'a> Day<'a> for Day01<'a> {
     const NUM: u32 = 1;
     type Output1 = u32;
     type Output2 = u32;
 
-    fn from_str(input: &str) -> Self {
-        let numbers = input
-            .chars()
-            .map(|c| c.to_digit(10).expect("Not a valid digit"))
-            .collect::<Vec<u32>>();
+    fn from_str(input: &'a str) -> Self {
+        let captcha = Captcha::new(input.as_bytes()).expect("Not a valid digit");
 
-        Day01 { numbers }
+        Day01 { captcha }
     }
 
     fn part_1(&self) -> u32 {
-        self.numbers
-            .iter()
-            .zip(self.numbers.iter().cycle().skip(1))
-            .fold(0, |acc, (number, next_number)| if number == next_number {
-                acc + number
-            } else {
-                acc
-            })
+        self.captcha.next_digit_sum()
     }
 
     fn part_2(&self) -> u32 {
-        self.numbers
-            .iter()
-            .zip(self.numbers.iter().cycle().skip(self.numbers.len() / 2))
-            .fold(0, |acc, (number, next_number)| if number == next_number {
-                acc + number
-            } else {
-                acc
-            })
+        self.captcha.halfway_sum()
     }
 }
 
@@ -0,0 +1,126 @@
+use std::fmt;
+
+/// A way of reducing a spreadsheet row to its contribution to the checksum.
+pub trait RowChecksum {
+    /// The row's contribution, or `None` if the strategy is undefined for this row.
+    fn row_value(&self, row: &[u32]) -> Option<u32>;
+}
+
+/// The difference between the largest and smallest values. Undefined for empty rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinMaxDifference;
+
+impl RowChecksum for MinMaxDifference {
+    fn row_value(&self, row: &[u32]) -> Option<u32> {
+        let max = row.iter().max()?;
+        let min = row.iter().min()?;
+
+        Some(max - min)
+    }
+}
+
+/// The quotient of the first pair of values where one evenly divides the other. Undefined for rows
+/// with no such pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvenDivision;
+
+impl RowChecksum for EvenDivision {
+    fn row_value(&self, row: &[u32]) -> Option<u32> {
+        let divides =
+            |divisor: u32, dividend: u32| divisor != 0 && dividend.is_multiple_of(divisor);
+
+        for i in 0..row.len() {
+            for j in (i + 1)..row.len() {
+                if divides(row[j], row[i]) {
+                    return Some(row[i] / row[j]);
+                } else if divides(row[i], row[j]) {
+                    return Some(row[j] / row[i]);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The rows, counted from 0, for which a checksum strategy was undefined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndefinedRows(pub Vec<usize>);
+
+impl fmt::Display for UndefinedRows {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "checksum is undefined for rows {:?}", self.0)
+    }
+}
+
+/// The contribution of every row of a spreadsheet to its checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    contributions: Vec<Option<u32>>,
+}
+
+impl Checksum {
+    pub fn new(contributions: Vec<Option<u32>>) -> Self {
+        Checksum { contributions }
+    }
+
+    /// Each row's contribution, in row order.
+    pub fn contributions(&self) -> &[Option<u32>] {
+        &self.contributions
+    }
+
+    pub fn undefined_rows(&self) -> Vec<usize> {
+        self.contributions
+            .iter()
+            .enumerate()
+            .filter(|&(_, value)| value.is_none())
+            .map(|(row, _)| row)
+            .collect()
+    }
+
+    /// The sum of the rows for which the strategy is defined.
+    pub fn partial_total(&self) -> u32 {
+        self.contributions.iter().filter_map(|&value| value).sum()
+    }
+
+    /// The checksum of the whole spreadsheet, if every row is defined.
+    pub fn total(&self) -> Result<u32, UndefinedRows> {
+        let undefined = self.undefined_rows();
+
+        if undefined.is_empty() {
+            Ok(self.partial_total())
+        } else {
+            Err(UndefinedRows(undefined))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_max_difference() {
+        assert_eq!(Some(8), MinMaxDifference.row_value(&[5, 1, 9, 5]));
+        assert_eq!(Some(0), MinMaxDifference.row_value(&[3]));
+        assert_eq!(None, MinMaxDifference.row_value(&[]));
+    }
+
+    #[test]
+    fn even_division() {
+        assert_eq!(Some(4), EvenDivision.row_value(&[5, 9, 2, 8]));
+        assert_eq!(Some(3), EvenDivision.row_value(&[9, 4, 7, 3]));
+        assert_eq!(Some(0), EvenDivision.row_value(&[0, 7]));
+        assert_eq!(None, EvenDivision.row_value(&[5, 7, 9]));
+        assert_eq!(None, EvenDivision.row_value(&[0]));
+    }
+
+    #[test]
+    fn undefined_rows() {
+        let checksum = Checksum::new(vec![Some(4), None, Some(2), None]);
+
+        assert_eq!(vec![1, 3], checksum.undefined_rows());
+        assert_eq!(6, checksum.partial_total());
+        assert_eq!(Err(UndefinedRows(vec![1, 3])), checksum.total());
+        assert_eq!(Ok(6), Checksum::new(vec![Some(4), Some(2)]).total());
+    }
+}
@@ -1,60 +1,44 @@
 use super::Day;
 
-use std::u32;
-use std::cmp;
+mod checksum;
+mod spreadsheet;
+
+pub use self::checksum::{Checksum, EvenDivision, MinMaxDifference, RowChecksum, UndefinedRows};
+pub use self::spreadsheet::{Delimiter, Spreadsheet, SpreadsheetError, SpreadsheetFormat};
 
 /// [Day 2](https://adventofcode.com/2017/day/2). Calculate the checksum of a spreadsheet.
 pub struct Day02 {
-    rows: Vec<Vec<u32>>,
+    spreadsheet: Spreadsheet,
 }
 
 impl Day02 {
-    fn row_map_reduce(&self, mapfn: fn(&[u32]) -> u32) -> isize {
-        self.rows.iter().map(|row| mapfn(row)).sum::<u32>() as isize
+    fn total<C: RowChecksum>(&self, strategy: &C) -> u32 {
+        self.spreadsheet
+            .checksum(strategy)
+            .total()
+            .unwrap_or_else(|err| panic!("Failed to calculate checksum: {}", err))
     }
 }
 
 impl<'a> Day<'a> for Day02 {
     const NUM: u32 = 2;
-    type Output1 = isize;
-    type Output2 = isize;
+    type Output1 = u32;
+    type Output2 = u32;
 
     fn from_str(input: &str) -> Self {
-        let rows = input
-            .lines()
-            .map(|line| {
-                line.split_whitespace()
-                    .map(|word| word.parse().unwrap())
-                    .collect()
-            })
-            .collect();
+        let spreadsheet = input
+            .parse::<Spreadsheet>()
+            .unwrap_or_else(|err| panic!("Failed to parse spreadsheet: {}", err));
 
-        Day02 { rows }
+        Day02 { spreadsheet }
     }
 
-    fn part_1(&self) -> isize {
-        self.row_map_reduce(|row| {
-            let (max, min) = row.iter().fold((0, u32::MAX), |(max, min), val| {
-                (cmp::max(max, *val), cmp::min(min, *val))
-            });
-
-            max - min
-        })
+    fn part_1(&self) -> u32 {
+        self.total(&MinMaxDifference)
     }
 
-    fn part_2(&self) -> isize {
-        self.row_map_reduce(|row| {
-            for i in 0..row.len() {
-                for j in (i + 1)..row.len() {
-                    if row[i] % row[j] == 0 {
-                        return row[i] / row[j];
-                    } else if row[j] % row[i] == 0 {
-                        return row[j] / row[i];
-                    }
-                }
-            }
-            unreachable!()
-        })
+    fn part_2(&self) -> u32 {
+        self.total(&EvenDivision)
     }
 }
 
@@ -0,0 +1,149 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::checksum::{Checksum, RowChecksum};
+
+/// How the cells in each row of a spreadsheet are separated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    /// Any run of spaces or tabs, as in the puzzle input.
+    Whitespace,
+    Comma,
+    Tab,
+}
+
+/// The layout of a spreadsheet's text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpreadsheetFormat {
+    pub delimiter: Delimiter,
+    /// Whether the first line is a header to be skipped.
+    pub header: bool,
+}
+
+impl Default for SpreadsheetFormat {
+    fn default() -> Self {
+        SpreadsheetFormat {
+            delimiter: Delimiter::Whitespace,
+            header: false,
+        }
+    }
+}
+
+/// A cell that could not be parsed. `line` and `column` are both counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpreadsheetError {
+    pub line: usize,
+    pub column: usize,
+    pub cell: String,
+}
+
+impl fmt::Display for SpreadsheetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid cell {:?} on line {}, column {}",
+            self.cell,
+            self.line,
+            self.column
+        )
+    }
+}
+
+/// Rows of unsigned numbers. Blank lines are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spreadsheet {
+    rows: Vec<Vec<u32>>,
+}
+
+impl FromStr for Spreadsheet {
+    type Err = SpreadsheetError;
+
+    /// Parse a whitespace separated spreadsheet without a header.
+    fn from_str(input: &str) -> Result<Self, SpreadsheetError> {
+        Spreadsheet::parse(input, &SpreadsheetFormat::default())
+    }
+}
+
+impl Spreadsheet {
+    pub fn parse(input: &str, format: &SpreadsheetFormat) -> Result<Self, SpreadsheetError> {
+        let skip = if format.header { 1 } else { 0 };
+        let mut rows = vec![];
+
+        for (index, line) in input.lines().enumerate().skip(skip) {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let cells: Vec<&str> = match format.delimiter {
+                Delimiter::Whitespace => line.split_whitespace().collect(),
+                Delimiter::Comma => line.split(',').map(str::trim).collect(),
+                Delimiter::Tab => line.split('\t').map(str::trim).collect(),
+            };
+
+            let row = cells
+                .iter()
+                .enumerate()
+                .map(|(column, cell)| {
+                    cell.parse().map_err(|_| SpreadsheetError {
+                        line: index + 1,
+                        column: column + 1,
+                        cell: cell.to_string(),
+                    })
+                })
+                .collect::<Result<Vec<u32>, _>>()?;
+
+            rows.push(row);
+        }
+
+        Ok(Spreadsheet { rows })
+    }
+
+    pub fn rows(&self) -> &[Vec<u32>] {
+        &self.rows
+    }
+
+    /// Apply a checksum strategy to every row.
+    pub fn checksum<C: RowChecksum + ?Sized>(&self, strategy: &C) -> Checksum {
+        Checksum::new(self.rows.iter().map(|row| strategy.row_value(row)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whitespace() {
+        let spreadsheet = "5 1\t9 5\n\n7 5 3\n".parse::<Spreadsheet>().unwrap();
+
+        assert_eq!(&[vec![5, 1, 9, 5], vec![7, 5, 3]], spreadsheet.rows());
+    }
+
+    #[test]
+    fn csv_with_header() {
+        let format = SpreadsheetFormat {
+            delimiter: Delimiter::Comma,
+            header: true,
+        };
+        let spreadsheet = Spreadsheet::parse("a,b,c\n5, 9,2\n9,4,7", &format).unwrap();
+
+        assert_eq!(&[vec![5, 9, 2], vec![9, 4, 7]], spreadsheet.rows());
+    }
+
+    #[test]
+    fn tsv_errors() {
+        let format = SpreadsheetFormat {
+            delimiter: Delimiter::Tab,
+            header: false,
+        };
+
+        assert_eq!(
+            Err(SpreadsheetError {
+                line: 2,
+                column: 2,
+                cell: "".to_string(),
+            }),
+            Spreadsheet::parse("1\t2\n3\t\t4", &format)
+        );
+    }
+}
@@ -1,48 +1,37 @@
-use std::collections::HashMap;
-use super::spiral_iter::SpiralIterator;
+use super::spiral_accumulator::{Neighbourhood, SpiralAccumulator};
 
+type Rule = fn(&[u64]) -> Option<u64>;
 
+/// The sum of the neighbours, or `None` if it overflows.
+fn sum(neighbours: &[u64]) -> Option<u64> {
+    neighbours.iter().try_fold(0u64, |acc, &n| acc.checked_add(n))
+}
+
+/// The spiral where the origin holds 1 and every later cell holds the sum of its eight
+/// neighbours. It ends before the first sum that does not fit in a `u64`.
 pub struct CachedSummedSpiralIterator {
-    history: HashMap<(i32, i32), i32>,
-    iter: Box<Iterator<Item = ((i32, i32), i32)>>,
+    iter: SpiralAccumulator<Rule>,
 }
 
 impl CachedSummedSpiralIterator {
     pub fn new() -> CachedSummedSpiralIterator {
-        let mut history = HashMap::new();
-        history.insert((0, 0), 1);
-        let iter = Box::new(SpiralIterator::new().skip(1));
+        let iter = SpiralAccumulator::new(1, Neighbourhood::Eight, sum as Rule);
+
+        CachedSummedSpiralIterator { iter }
+    }
+}
 
-        CachedSummedSpiralIterator { history, iter }
+impl Default for CachedSummedSpiralIterator {
+    fn default() -> Self {
+        CachedSummedSpiralIterator::new()
     }
 }
 
 impl Iterator for CachedSummedSpiralIterator {
-    type Item = ((i32, i32), i32);
+    type Item = ((i64, i64), u64);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(((x, y), _)) = self.iter.next() {
-            let sum_of_neighbours = [
-                (x - 1, y + 1),
-                (x, y + 1),
-                (x + 1, y + 1),
-                (x - 1, y),
-                (x + 1, y),
-                (x - 1, y - 1),
-                (x, y - 1),
-                (x + 1, y - 1),
-            ].into_iter()
-                .map(|pos| {
-                    let val = self.history.get(&pos).map(|&n| n).unwrap_or(0);
-                    val
-                })
-                .sum::<i32>();
-            self.history.insert((x, y), sum_of_neighbours);
-
-            Some(((x, y), sum_of_neighbours))
-        } else {
-            None
-        }
+        self.iter.next()
     }
 }
 
@@ -50,6 +39,7 @@ impl Iterator for CachedSummedSpiralIterator {
 fn test_cached_spiral_iter() {
     let mut iter = CachedSummedSpiralIterator::new();
 
+    assert_eq!(Some(((0, 0), 1)), iter.next());
     assert_eq!(Some(((1, 0), 1)), iter.next());
     assert_eq!(Some(((1, 1), 2)), iter.next());
     assert_eq!(Some(((0, 1), 4)), iter.next());
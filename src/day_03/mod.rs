@@ -1,42 +1,45 @@
 pub use super::Day;
 
 mod spiral_iter;
-use self::spiral_iter::SpiralIterator;
+pub use self::spiral_iter::{spiral_number, spiral_position, SpiralIterator};
+
+mod spiral_accumulator;
+pub use self::spiral_accumulator::{Neighbourhood, SpiralAccumulator};
 
 mod cached_summed_spiral_iter;
-use self::cached_summed_spiral_iter::CachedSummedSpiralIterator;
+pub use self::cached_summed_spiral_iter::CachedSummedSpiralIterator;
+
+mod render;
+pub use self::render::render_spiral;
 
 /// [Day 3](https://adventofcode.com/2017/day/3). Calculate the Manhatten distance of a number from
 /// the centre of number spirals.
 pub struct Day03 {
-    number: i32,
+    number: u64,
 }
 
 impl<'a> Day<'a> for Day03 {
     const NUM: u32 = 3;
-    type Output1 = i32;
-    type Output2 = i32;
+    type Output1 = u64;
+    type Output2 = u64;
 
     fn from_str(input: &str) -> Self {
         Day03 { number: input.parse().unwrap() }
     }
 
-    fn part_1(&self) -> i32 {
-        let mut iter = SpiralIterator::new().skip_while(|&(_, number)| number != self.number);
+    fn part_1(&self) -> u64 {
+        let (x, y) = spiral_position(self.number);
 
-        match iter.next() {
-            Some(((x, y), _)) => (x.abs() + y.abs()),
-            None => unreachable!(),
-        }
+        x.unsigned_abs() + y.unsigned_abs()
     }
 
-    fn part_2(&self) -> i32 {
+    fn part_2(&self) -> u64 {
         let mut iter =
             CachedSummedSpiralIterator::new().skip_while(|&(_, number)| number < self.number);
 
         match iter.next() {
             Some((_, value)) => value,
-            None => unreachable!(),
+            None => panic!("Every summed spiral value that fits in a u64 is below {}", self.number),
         }
     }
 }
@@ -49,6 +52,8 @@ mod tests {
     pub fn part_1() {
         assert_eq!(0, Day03::from_str("1").part_1());
         assert_eq!(3, Day03::from_str("12").part_1());
+        assert_eq!(31, Day03::from_str("1024").part_1());
+        assert_eq!(65_536, Day03::from_str("4294967297").part_1());
     }
 
     #[test]
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+/// How a cell is drawn: the target is wrapped in `[ ]` and the cells on its Manhattan path back
+/// to the origin in `( )`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Highlight {
+    Path,
+    Target,
+}
+
+/// The cells from the target to the origin, moving horizontally and then vertically.
+fn manhattan_path((mut x, mut y): (i64, i64)) -> Vec<(i64, i64)> {
+    let mut path = vec![];
+
+    while x != 0 {
+        x -= x.signum();
+        path.push((x, y));
+    }
+    while y != 0 {
+        y -= y.signum();
+        path.push((x, y));
+    }
+    path
+}
+
+/// Draw the cells of a spiral out to `radius` rings from the origin as an aligned grid, with
+/// positive `y` at the top. The cells must be in spiral order, as produced by `SpiralIterator` or
+/// `CachedSummedSpiralIterator`, and positions that never appear are left blank.
+///
+/// If `target` is given, the first cell holding that value is highlighted along with its path
+/// back to the origin.
+pub fn render_spiral<I>(cells: I, radius: u64, target: Option<u64>) -> String
+where
+    I: IntoIterator<Item = ((i64, i64), u64)>,
+{
+    let radius = radius as i64;
+    let values: Vec<_> = cells
+        .into_iter()
+        .take_while(|&((x, y), _)| x.abs() <= radius && y.abs() <= radius)
+        .collect();
+
+    let mut highlights = HashMap::new();
+    if let Some(&(position, _)) = values.iter().find(|&&(_, value)| Some(value) == target) {
+        for step in manhattan_path(position) {
+            highlights.insert(step, Highlight::Path);
+        }
+        highlights.insert(position, Highlight::Target);
+    }
+
+    let width = values
+        .iter()
+        .map(|&(_, value)| value.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let grid: HashMap<_, _> = values.into_iter().collect();
+
+    let mut lines = vec![];
+    for y in (-radius..=radius).rev() {
+        let mut line = String::new();
+
+        for x in -radius..=radius {
+            let value = grid.get(&(x, y))
+                .map(|value| value.to_string())
+                .unwrap_or_default();
+            let (open, close) = match highlights.get(&(x, y)) {
+                Some(&Highlight::Target) => ('[', ']'),
+                Some(&Highlight::Path) => ('(', ')'),
+                None => (' ', ' '),
+            };
+
+            line.push_str(&format!("{}{:>width$}{}", open, value, close, width = width));
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{CachedSummedSpiralIterator, SpiralIterator};
+
+    #[test]
+    fn plain_spiral() {
+        let expected = " 5  4  3\n 6 (1) 2\n 7 (8)[9]";
+
+        assert_eq!(expected, render_spiral(SpiralIterator::new(), 1, Some(9)));
+    }
+
+    #[test]
+    fn aligned_columns() {
+        let expected = [
+            " 17  16  15  14  13",
+            " 18   5   4   3  12",
+            " 19   6 ( 1)( 2)[11]",
+            " 20   7   8   9  10",
+            " 21  22  23  24  25",
+        ].join("\n");
+
+        assert_eq!(expected, render_spiral(SpiralIterator::new(), 2, Some(11)));
+    }
+
+    #[test]
+    fn summed_spiral() {
+        let expected = "  5   4   2\n 10 ( 1)  1\n 11 [23] 25";
+
+        assert_eq!(
+            expected,
+            render_spiral(CachedSummedSpiralIterator::new(), 1, Some(23))
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use std::mem;
+
+use super::spiral_iter::{spiral_number, spiral_position};
+use super::super::util::grid::{OFFSETS_4, OFFSETS_8};
+
+/// Which of a cell's neighbours a `SpiralAccumulator` rule can see.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// The orthogonal neighbours only.
+    Four,
+    /// The orthogonal and diagonal neighbours.
+    Eight,
+}
+
+impl Neighbourhood {
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match *self {
+            Neighbourhood::Four => &OFFSETS_4,
+            Neighbourhood::Eight => &OFFSETS_8,
+        }
+    }
+}
+
+/// Fills the spiral in order, starting with `seed` at the origin and giving every later cell the
+/// value of `rule` applied to its neighbours that have already been filled. The spiral ends the
+/// first time `rule` returns `None`, for example when a value would overflow.
+///
+/// A cell's neighbours all lie in its own ring or the rings either side of it, so only the
+/// previous and current rings are kept. Each is stored in spiral order, and a neighbour is found
+/// by converting its position to its number in the spiral.
+pub struct SpiralAccumulator<F> {
+    rule: F,
+    neighbourhood: Neighbourhood,
+    previous: Vec<u64>,
+    current: Vec<u64>,
+    ring: u64,
+    number: u64,
+    finished: bool,
+}
+
+/// The ring of the spiral that `(x, y)` lies in, where the origin is ring 0.
+fn ring_of(x: i64, y: i64) -> u64 {
+    x.unsigned_abs().max(y.unsigned_abs())
+}
+
+/// The spiral number of the first cell in `ring`.
+fn ring_start(ring: u64) -> u64 {
+    match ring {
+        0 => 1,
+        _ => (2 * ring - 1) * (2 * ring - 1) + 1,
+    }
+}
+
+impl<F> SpiralAccumulator<F>
+where
+    F: FnMut(&[u64]) -> Option<u64>,
+{
+    pub fn new(seed: u64, neighbourhood: Neighbourhood, rule: F) -> SpiralAccumulator<F> {
+        SpiralAccumulator {
+            rule,
+            neighbourhood,
+            previous: vec![],
+            current: vec![seed],
+            ring: 0,
+            number: 0,
+            finished: false,
+        }
+    }
+
+    /// The value at `(x, y)`, if it has been filled and is in the previous or current ring.
+    pub fn get(&self, x: i64, y: i64) -> Option<u64> {
+        let ring = ring_of(x, y);
+        let values = if ring == self.ring {
+            &self.current
+        } else if ring + 1 == self.ring {
+            &self.previous
+        } else {
+            return None;
+        };
+
+        let index = (spiral_number(x, y)? - ring_start(ring)) as usize;
+        values.get(index).cloned()
+    }
+}
+
+impl<F> Iterator for SpiralAccumulator<F>
+where
+    F: FnMut(&[u64]) -> Option<u64>,
+{
+    type Item = ((i64, i64), u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        self.number += 1;
+        if self.number == 1 {
+            return Some(((0, 0), self.current[0]));
+        }
+
+        // Only the cells before this one in the spiral have been filled.
+        let (x, y) = spiral_position(self.number);
+        let ring = ring_of(x, y);
+        if ring != self.ring {
+            let current = Vec::with_capacity(8 * ring as usize);
+            self.previous = mem::replace(&mut self.current, current);
+            self.ring = ring;
+        }
+
+        let mut neighbours = [0; 8];
+        let mut count = 0;
+        for &(dx, dy) in self.neighbourhood.offsets() {
+            if let Some(value) = self.get(x + dx as i64, y + dy as i64) {
+                neighbours[count] = value;
+                count += 1;
+            }
+        }
+
+        let value = match (self.rule)(&neighbours[..count]) {
+            Some(value) => value,
+            None => {
+                self.finished = true;
+                return None;
+            }
+        };
+        self.current.push(value);
+
+        Some(((x, y), value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum(neighbours: &[u64]) -> Option<u64> {
+        neighbours.iter().try_fold(0u64, |acc, &n| acc.checked_add(n))
+    }
+
+    #[test]
+    fn summed_eight_neighbours() {
+        let values = SpiralAccumulator::new(1, Neighbourhood::Eight, sum)
+            .map(|(_, value)| value)
+            .take(12)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![1, 1, 2, 4, 5, 10, 11, 23, 25, 26, 54, 57], values);
+    }
+
+    #[test]
+    fn summed_four_neighbours() {
+        let values = SpiralAccumulator::new(1, Neighbourhood::Four, sum)
+            .map(|(_, value)| value)
+            .take(11)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![1, 1, 1, 2, 2, 3, 3, 4, 5, 5, 6], values);
+    }
+
+    #[test]
+    fn other_rules() {
+        let count_non_zero =
+            |neighbours: &[u64]| Some(neighbours.iter().filter(|&&n| n != 0).count() as u64 + 1);
+        let mut counts = SpiralAccumulator::new(0, Neighbourhood::Eight, count_non_zero);
+        assert_eq!(Some(((0, 0), 0)), counts.next());
+        assert_eq!(Some(((1, 0), 1)), counts.next());
+        assert_eq!(Some(((1, 1), 2)), counts.next());
+        assert_eq!(Some(((0, 1), 3)), counts.next());
+
+        let max_plus_one = |neighbours: &[u64]| neighbours.iter().max().map(|max| max + 1);
+        let mut maxes = SpiralAccumulator::new(0, Neighbourhood::Four, max_plus_one);
+        assert_eq!(8, maxes.nth(8).unwrap().1);
+        assert_eq!(Some(5), maxes.get(-1, 0));
+        assert_eq!(None, maxes.get(2, -1));
+
+        let product_mod =
+            |neighbours: &[u64]| Some(neighbours.iter().fold(2, |acc, n| acc * n % 7));
+        let products = SpiralAccumulator::new(3, Neighbourhood::Eight, product_mod)
+            .take(4)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![((0, 0), 3), ((1, 0), 6), ((1, 1), 1), ((0, 1), 1)],
+            products
+        );
+    }
+
+    #[test]
+    fn keeps_two_rings() {
+        let mut spiral = SpiralAccumulator::new(1, Neighbourhood::Eight, sum);
+
+        // The first cell of ring 2.
+        assert_eq!(((2, -1), 26), spiral.nth(9).unwrap());
+        assert_eq!(Some(25), spiral.get(1, -1));
+        assert_eq!(None, spiral.get(0, 0));
+        assert_eq!(None, spiral.get(2, 0));
+    }
+
+    #[test]
+    fn ends_on_overflow() {
+        let mut spiral = SpiralAccumulator::new(1, Neighbourhood::Eight, sum);
+        let last = spiral.by_ref().last().unwrap();
+
+        // The values only ever grow, so the spiral stops close to the top of the range.
+        assert!(last.1 > u64::MAX / 8);
+        assert_eq!(None, spiral.next());
+    }
+}
@@ -1,5 +1,54 @@
+/// The position of `number` in the spiral, with 1 at the origin, 2 at `(1, 0)` and the spiral
+/// turning anticlockwise. Panics if `number` is 0.
+pub fn spiral_position(number: u64) -> (i64, i64) {
+    assert!(number > 0, "The spiral starts at 1");
+    if number == 1 {
+        return (0, 0);
+    }
+
+    // Ring `k` holds the numbers after `(2k - 1)^2` up to and including `(2k + 1)^2`.
+    let k = (number - 1).isqrt().div_ceil(2);
+    let side = 2 * k;
+    let steps = number - 1 - (side - 1) * (side - 1);
+
+    let (k, along) = (k as i64, (steps % side) as i64);
+    match steps / side {
+        0 => (k, -k + 1 + along),
+        1 => (k - 1 - along, k),
+        2 => (-k, k - 1 - along),
+        _ => (-k + 1 + along, -k),
+    }
+}
+
+/// The number at position `(x, y)` in the spiral, or `None` if it does not fit in a `u64`. The
+/// inverse of `spiral_position`.
+pub fn spiral_number(x: i64, y: i64) -> Option<u64> {
+    let k = x.unsigned_abs().max(y.unsigned_abs());
+    if k == 0 {
+        return Some(1);
+    }
+
+    // The ring only starts within range if `k` is at most 2^31, so the steps around it can't
+    // overflow once this has been checked.
+    let side = k.checked_mul(2)?;
+    let ring_start = (side - 1).checked_mul(side - 1)?.checked_add(1)?;
+
+    let k = k as i64;
+    let steps = if x == k && y > -k {
+        y + k - 1
+    } else if y == k {
+        2 * k + (k - 1 - x)
+    } else if x == -k {
+        4 * k + (k - 1 - y)
+    } else {
+        6 * k + (x + k - 1)
+    };
+
+    ring_start.checked_add(steps as u64)
+}
+
 pub struct SpiralIterator {
-    number: i32,
+    number: u64,
 }
 
 impl SpiralIterator {
@@ -8,32 +57,24 @@ impl SpiralIterator {
     }
 }
 
+impl Default for SpiralIterator {
+    fn default() -> Self {
+        SpiralIterator::new()
+    }
+}
+
 
 impl Iterator for SpiralIterator {
-    type Item = ((i32, i32), i32);
+    type Item = ((i64, i64), u64);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.number += 1;
-        let n = (((self.number as f64).sqrt() - 1.0) / 2.0).ceil() as i32;
-
-        let top_right = 4 * n * n - 2 * n + 1;
-        let top_left = 4 * n * n + 1;
-        let bottom_left = 4 * n * n + 2 * n + 1;
-        let bottom_right = 4 * n * n + 4 * n + 1;
+        self.nth(0)
+    }
 
-        let (x, y) = if self.number < top_right {
-            (n, n + self.number - top_right)
-        } else if top_right <= self.number && self.number < top_left {
-            (n + top_right - self.number, n)
-        } else if top_left <= self.number && self.number < bottom_left {
-            (-n, n + top_left - self.number)
-        } else if bottom_left <= self.number && self.number < bottom_right {
-            (-n + (self.number - bottom_left), -n)
-        } else {
-            (n + self.number - bottom_right, -n)
-        };
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.number = self.number.checked_add(n as u64)?.checked_add(1)?;
 
-        Some(((x, y), self.number))
+        Some((spiral_position(self.number), self.number))
     }
 }
 
@@ -57,3 +98,53 @@ fn test() {
     assert_eq!(Some(((1, 2), 14)), iter.next());
     assert_eq!(Some(((0, 2), 15)), iter.next());
 }
+
+#[test]
+fn test_nth() {
+    let mut iter = SpiralIterator::new();
+
+    assert_eq!(Some(((2, 2), 13)), iter.nth(12));
+    assert_eq!(Some(((0, 2), 15)), iter.nth(1));
+    assert_eq!(Some(((-2, 2), 17)), iter.nth(1));
+    assert_eq!(Some(((0, -2), 23)), iter.nth(5));
+}
+
+#[test]
+fn test_position_and_number_are_inverses() {
+    let mut iter = SpiralIterator::new();
+
+    for number in 1..10_000 {
+        let (position, _) = iter.next().unwrap();
+        assert_eq!(Some(number), spiral_number(position.0, position.1));
+    }
+
+    for &number in &[
+        u64::from(u32::MAX),
+        i32::MAX as u64 + 12_345,
+        (1 << 40) + 7,
+        u64::MAX,
+    ] {
+        let (x, y) = spiral_position(number);
+        assert_eq!(Some(number), spiral_number(x, y));
+    }
+
+    let corner = 4_000_000_001 * 4_000_000_001;
+    assert_eq!((2_000_000_000, -2_000_000_000), spiral_position(corner));
+    assert_eq!(Some(corner), spiral_number(2_000_000_000, -2_000_000_000));
+}
+
+#[test]
+fn test_number_out_of_range() {
+    // Ring 2^31 is the last to start within range, but it ends past `u64::MAX`.
+    let k = 1 << 31;
+    let top_right = u64::MAX - (1 << 32) + 2;
+    assert_eq!(Some(top_right), spiral_number(k, k));
+    assert_eq!((k, k), spiral_position(top_right));
+    assert_eq!(None, spiral_number(k, -k));
+    assert_eq!(None, spiral_number(k + 1, 0));
+
+    assert_eq!(None, spiral_number(i64::MAX / 2, 0));
+    assert_eq!(None, spiral_number(i64::MAX, 0));
+    assert_eq!(None, spiral_number(0, i64::MIN));
+    assert_eq!(None, spiral_number(i64::MIN, i64::MIN));
+}
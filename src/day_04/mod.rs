@@ -1,32 +1,29 @@
 use super::Day;
 
-use std::collections::HashSet;
+mod policy;
+
+pub use self::policy::{CharClass, Normalisation, PassphrasePolicy, PassphraseRule,
+                       PolicyViolation};
 
 /// [Day 04](https://adventofcode.com/2017/day/4) - Calculate how many of a series of passphrases
 /// are valid.
-pub struct Day04 {
-    passphrases: Vec<String>,
+pub struct Day04<'a> {
+    passphrases: &'a str,
 }
 
-impl Day04 {
+impl<'a> Day04<'a> {
     /// Checks if all the words in the passphrase are unique.
     pub fn is_passphrase_valid(passphrase: &str) -> bool {
-        let mut set: HashSet<String> = HashSet::new();
-
-        passphrase.split_whitespace().all(|word| {
-            set.insert(word.to_owned())
-        })
+        PassphrasePolicy::new(vec![PassphraseRule::UniqueWords]).is_valid(passphrase)
     }
 
     /// Checks if all the words in a passphrase are unique, including reordering of characters.
     pub fn is_passphrase_even_more_valid(passphrase: &str) -> bool {
-        let mut set: HashSet<String> = HashSet::new();
+        PassphrasePolicy::new(vec![PassphraseRule::NoAnagrams]).is_valid(passphrase)
+    }
 
-        passphrase.split_whitespace().all(|word| {
-            let mut word_chars = word.chars().collect::<Vec<_>>();
-            word_chars.sort();
-            set.insert(word_chars.into_iter().collect())
-        })
+    fn count_valid(&self, policy: &PassphrasePolicy) -> usize {
+        policy.count_valid(self.passphrases)
     }
 }
 
@@ -63,28 +60,20 @@ mod tests {
     }
 }
 
-impl<'a> Day<'a> for Day04 {
+impl<'a> Day<'a> for Day04<'a> {
     const NUM: u32 = 4;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn from_str(input: &str) -> Day04 {
-        let passphrases = input.lines().map(|line| line.to_owned()).collect();
-
-        Day04 { passphrases }
+    fn from_str(input: &'a str) -> Day04<'a> {
+        Day04 { passphrases: input }
     }
 
     fn part_1(&self) -> usize {
-        self.passphrases
-            .iter()
-            .filter(|phrase| Day04::is_passphrase_valid(phrase))
-            .count()
+        self.count_valid(&PassphrasePolicy::new(vec![PassphraseRule::UniqueWords]))
     }
 
     fn part_2(&self) -> usize {
-        self.passphrases
-            .iter()
-            .filter(|phrase| Day04::is_passphrase_even_more_valid(phrase))
-            .count()
+        self.count_valid(&PassphrasePolicy::new(vec![PassphraseRule::NoAnagrams]))
     }
 }
\ No newline at end of file
@@ -0,0 +1,404 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::thread;
+
+use unicode_normalization::UnicodeNormalization;
+
+/// The characters a word in a passphrase may be made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    AsciiLowercase,
+    AsciiAlphanumeric,
+    Alphabetic,
+    Alphanumeric,
+}
+
+impl CharClass {
+    pub fn contains(&self, c: char) -> bool {
+        match *self {
+            CharClass::AsciiLowercase => c.is_ascii_lowercase(),
+            CharClass::AsciiAlphanumeric => c.is_ascii_alphanumeric(),
+            CharClass::Alphabetic => c.is_alphabetic(),
+            CharClass::Alphanumeric => c.is_alphanumeric(),
+        }
+    }
+}
+
+/// A single requirement of a `PassphrasePolicy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassphraseRule {
+    /// No word may appear twice.
+    UniqueWords,
+    /// No word may be a rearrangement of the letters of another.
+    NoAnagrams,
+    MinWords(usize),
+    MaxWords(usize),
+    /// Every character of every word must be in the class.
+    Chars(CharClass),
+}
+
+/// How words are normalised before they are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalisation {
+    /// Canonical composition, so that `é` and `e\u{301}` are the same word.
+    Nfc,
+    /// Compatibility composition, which also treats characters such as `ﬁ` and `fi` as the same.
+    Nfkc,
+}
+
+/// The first rule a passphrase broke, with the words that broke it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyViolation {
+    DuplicateWord { first: String, second: String },
+    Anagram { first: String, second: String },
+    TooFewWords { count: usize, min: usize },
+    TooManyWords { count: usize, max: usize },
+    InvalidChar { word: String, c: char, class: CharClass },
+}
+
+impl PolicyViolation {
+    /// The rule that was broken.
+    pub fn rule(&self) -> PassphraseRule {
+        match *self {
+            PolicyViolation::DuplicateWord { .. } => PassphraseRule::UniqueWords,
+            PolicyViolation::Anagram { .. } => PassphraseRule::NoAnagrams,
+            PolicyViolation::TooFewWords { min, .. } => PassphraseRule::MinWords(min),
+            PolicyViolation::TooManyWords { max, .. } => PassphraseRule::MaxWords(max),
+            PolicyViolation::InvalidChar { class, .. } => PassphraseRule::Chars(class),
+        }
+    }
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PolicyViolation::DuplicateWord {
+                ref first,
+                ref second,
+            } => write!(f, "{:?} and {:?} are the same word", first, second),
+            PolicyViolation::Anagram {
+                ref first,
+                ref second,
+            } => write!(f, "{:?} and {:?} are anagrams", first, second),
+            PolicyViolation::TooFewWords { count, min } => {
+                write!(f, "{} words is fewer than the minimum of {}", count, min)
+            }
+            PolicyViolation::TooManyWords { count, max } => {
+                write!(f, "{} words is more than the maximum of {}", count, max)
+            }
+            PolicyViolation::InvalidChar {
+                ref word,
+                c,
+                class,
+            } => write!(f, "{:?} in {:?} is not in {:?}", c, word, class),
+        }
+    }
+}
+
+/// A set of rules that passphrases must follow. Words are separated by whitespace and compared
+/// after normalisation and, if `case_insensitive` is set, lowercasing.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PassphrasePolicy {
+    /// Checked in order, stopping at the first broken rule.
+    pub rules: Vec<PassphraseRule>,
+    pub case_insensitive: bool,
+    pub normalisation: Option<Normalisation>,
+}
+
+/// A word's letters, ignoring their order. Words of up to 255 ASCII lowercase letters are counted
+/// into a fixed-size array, and any other word falls back to its sorted characters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Signature {
+    Letters([u8; 26]),
+    Sorted(Vec<char>),
+}
+
+impl Signature {
+    fn of(word: &str) -> Signature {
+        if word.len() <= usize::from(u8::MAX) && word.bytes().all(|b| b.is_ascii_lowercase()) {
+            let mut counts = [0; 26];
+            for b in word.bytes() {
+                counts[usize::from(b - b'a')] += 1;
+            }
+            Signature::Letters(counts)
+        } else {
+            let mut chars = word.chars().collect::<Vec<_>>();
+            chars.sort_unstable();
+            Signature::Sorted(chars)
+        }
+    }
+}
+
+/// Split `input` into at most `parts` pieces of roughly equal length, each ending at a line break.
+fn split_lines(input: &str, parts: usize) -> Vec<&str> {
+    let chunk_size = input.len().div_ceil(parts).max(1);
+    let mut chunks = vec![];
+    let mut start = 0;
+
+    while start < input.len() {
+        let end = (start + chunk_size).min(input.len());
+        let end = input.as_bytes()[end - 1..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(input.len(), |newline| end + newline);
+
+        chunks.push(&input[start..end]);
+        start = end;
+    }
+    chunks
+}
+
+/// The first pair of indices whose keys are equal.
+fn find_collision<K: Hash + Eq>(keys: impl Iterator<Item = K>) -> Option<(usize, usize)> {
+    let mut seen = HashMap::new();
+
+    for (index, key) in keys.enumerate() {
+        if let Some(&first) = seen.get(&key) {
+            return Some((first, index));
+        }
+        seen.insert(key, index);
+    }
+    None
+}
+
+impl PassphrasePolicy {
+    pub fn new(rules: Vec<PassphraseRule>) -> PassphrasePolicy {
+        PassphrasePolicy {
+            rules,
+            ..PassphrasePolicy::default()
+        }
+    }
+
+    /// The form of a word used for comparisons, which borrows the word unless it has to change.
+    fn key<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        let key = match self.normalisation {
+            Some(Normalisation::Nfc) => Cow::Owned(word.nfc().collect()),
+            Some(Normalisation::Nfkc) => Cow::Owned(word.nfkc().collect()),
+            None => Cow::Borrowed(word),
+        };
+
+        if self.case_insensitive && key.chars().any(char::is_uppercase) {
+            Cow::Owned(key.to_lowercase())
+        } else {
+            key
+        }
+    }
+
+    /// Check a passphrase against every rule, returning the first that it breaks.
+    pub fn validate(&self, passphrase: &str) -> Result<(), PolicyViolation> {
+        let words = passphrase.split_whitespace().collect::<Vec<_>>();
+        let keys = || words.iter().map(|word| self.key(word));
+        let pair = |(first, second): (usize, usize)| {
+            (words[first].to_string(), words[second].to_string())
+        };
+
+        for rule in self.rules.iter() {
+            match *rule {
+                PassphraseRule::UniqueWords => {
+                    if let Some(collision) = find_collision(keys()) {
+                        let (first, second) = pair(collision);
+                        return Err(PolicyViolation::DuplicateWord { first, second });
+                    }
+                }
+                PassphraseRule::NoAnagrams => {
+                    let signatures = keys().map(|key| Signature::of(&key));
+
+                    if let Some(collision) = find_collision(signatures) {
+                        let (first, second) = pair(collision);
+                        return Err(PolicyViolation::Anagram { first, second });
+                    }
+                }
+                PassphraseRule::MinWords(min) => {
+                    if words.len() < min {
+                        return Err(PolicyViolation::TooFewWords {
+                            count: words.len(),
+                            min,
+                        });
+                    }
+                }
+                PassphraseRule::MaxWords(max) => {
+                    if words.len() > max {
+                        return Err(PolicyViolation::TooManyWords {
+                            count: words.len(),
+                            max,
+                        });
+                    }
+                }
+                PassphraseRule::Chars(class) => {
+                    for word in words.iter() {
+                        if let Some(c) = word.chars().find(|&c| !class.contains(c)) {
+                            return Err(PolicyViolation::InvalidChar {
+                                word: word.to_string(),
+                                c,
+                                class,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_valid(&self, passphrase: &str) -> bool {
+        self.validate(passphrase).is_ok()
+    }
+
+    /// The number of valid lines in `input`.
+    pub fn count_valid(&self, input: &str) -> usize {
+        input.lines().filter(|line| self.is_valid(line)).count()
+    }
+
+    /// The number of valid lines in `input`, checked in chunks on `threads` threads.
+    pub fn count_valid_parallel(&self, input: &str, threads: usize) -> usize {
+        assert!(threads > 0, "At least one thread is needed");
+
+        thread::scope(|scope| {
+            let handles = split_lines(input, threads)
+                .into_iter()
+                .map(|chunk| scope.spawn(move || self.count_valid(chunk)))
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("Passphrase thread panicked"))
+                .sum()
+        })
+    }
+
+    /// Every invalid line of `input`, counted from 1, with the rule it broke.
+    pub fn audit(&self, input: &str) -> Vec<(usize, PolicyViolation)> {
+        input
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| self.validate(line).err().map(|err| (index + 1, err)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_colliding_words() {
+        let policy = PassphrasePolicy::new(vec![
+            PassphraseRule::UniqueWords,
+            PassphraseRule::NoAnagrams,
+        ]);
+
+        assert_eq!(Ok(()), policy.validate("abcde fghij"));
+        assert_eq!(
+            Err(PolicyViolation::DuplicateWord {
+                first: "aa".to_string(),
+                second: "aa".to_string(),
+            }),
+            policy.validate("aa bb cc dd aa")
+        );
+        assert_eq!(
+            Err(PolicyViolation::Anagram {
+                first: "abcde".to_string(),
+                second: "ecdab".to_string(),
+            }),
+            policy.validate("abcde xyz ecdab")
+        );
+    }
+
+    #[test]
+    fn word_counts_and_chars() {
+        let policy = PassphrasePolicy::new(vec![
+            PassphraseRule::MinWords(2),
+            PassphraseRule::MaxWords(3),
+            PassphraseRule::Chars(CharClass::AsciiLowercase),
+        ]);
+
+        assert!(policy.is_valid("aa bb"));
+        assert_eq!(
+            Err(PolicyViolation::TooFewWords { count: 1, min: 2 }),
+            policy.validate("aa")
+        );
+        assert_eq!(
+            Err(PolicyViolation::TooManyWords { count: 4, max: 3 }),
+            policy.validate("aa bb cc dd")
+        );
+
+        let violation = policy.validate("aa b2").unwrap_err();
+        assert_eq!(PassphraseRule::Chars(CharClass::AsciiLowercase), violation.rule());
+        assert_eq!(
+            "'2' in \"b2\" is not in AsciiLowercase",
+            violation.to_string()
+        );
+    }
+
+    #[test]
+    fn case_and_normalisation() {
+        let mut policy = PassphrasePolicy::new(vec![PassphraseRule::UniqueWords]);
+        assert!(policy.is_valid("Caf\u{e9} cafe\u{301}"));
+
+        policy.case_insensitive = true;
+        policy.normalisation = Some(Normalisation::Nfc);
+        assert_eq!(
+            Err(PolicyViolation::DuplicateWord {
+                first: "Caf\u{e9}".to_string(),
+                second: "cafe\u{301}".to_string(),
+            }),
+            policy.validate("Caf\u{e9} cafe\u{301}")
+        );
+
+        assert!(policy.is_valid("\u{fb01}x fix"));
+        policy.normalisation = Some(Normalisation::Nfkc);
+        assert!(!policy.is_valid("\u{fb01}x fix"));
+    }
+
+    #[test]
+    fn signatures() {
+        assert_eq!(Signature::of("listen"), Signature::of("silent"));
+        assert_ne!(Signature::of("aab"), Signature::of("abb"));
+        assert_eq!(Signature::of("\u{e9}t\u{e9}"), Signature::of("t\u{e9}\u{e9}"));
+        assert_eq!(Signature::Sorted(vec!['A', 'a']), Signature::of("aA"));
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let mut policy = PassphrasePolicy::default();
+        assert!(matches!(policy.key("abc"), Cow::Borrowed("abc")));
+
+        policy.case_insensitive = true;
+        assert!(matches!(policy.key("abc"), Cow::Borrowed("abc")));
+        assert_eq!("abc", policy.key("aBc"));
+    }
+
+    #[test]
+    fn parallel_count() {
+        let policy = PassphrasePolicy::new(vec![PassphraseRule::NoAnagrams]);
+        let input = (0..1000)
+            .map(|i| format!("a{} b{} {}a", i % 7, i % 5, i % 3))
+            .collect::<Vec<_>>()
+            .join("\r\n");
+        let expected = policy.count_valid(&input);
+
+        assert_eq!(856, expected);
+        for threads in 1..10 {
+            assert_eq!(expected, policy.count_valid_parallel(&input, threads));
+        }
+        assert_eq!(0, policy.count_valid_parallel("", 4));
+    }
+
+    #[test]
+    fn split_lines_at_line_breaks() {
+        assert_eq!(vec!["ab\n", "cd\n", "e"], split_lines("ab\ncd\ne", 3));
+        assert_eq!(vec!["abcdef"], split_lines("abcdef", 4));
+        assert!(split_lines("", 2).is_empty());
+    }
+
+    #[test]
+    fn audit() {
+        let policy = PassphrasePolicy::new(vec![PassphraseRule::NoAnagrams]);
+        let report = policy.audit("ab cd\nab ba\nabc\ncd dc cd");
+
+        assert_eq!(vec![2, 4], report.iter().map(|&(line, _)| line).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,174 @@
+//! A specialised solver for part 2, where offsets of three or more decrease and others increase.
+//!
+//! Under that rule an offset of 2 becomes 3 and an offset of 3 becomes 2, so once a cell holds
+//! either value it stays in that pair forever. Cells with other values move towards the pair, so
+//! the start of the maze soon settles into 2s and 3s, and most of the steps are spent walking
+//! forwards through that settled prefix. The prefix is stored as one bit per cell, in chunks of
+//! `CHUNK` cells, and a walk through a whole chunk is looked up in a precomputed table.
+
+use std::convert::TryFrom;
+use std::sync::OnceLock;
+
+use super::jump_maze::JumpMaze;
+
+const CHUNK: usize = 12;
+
+/// The result of entering a settled chunk with bit pattern `state` (a set bit is an offset of 3)
+/// at position `entry`, packed as `state | steps << 16 | overshoot << 24`. `overshoot` is how far
+/// past the end of the chunk the final jump lands.
+fn chunk_table() -> &'static [u32] {
+    static TABLE: OnceLock<Vec<u32>> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        let mut table = Vec::with_capacity((1 << CHUNK) * CHUNK);
+
+        for state in 0..(1u32 << CHUNK) {
+            for entry in 0..CHUNK {
+                let (mut state, mut pos, mut steps) = (state, entry, 0);
+
+                while pos < CHUNK {
+                    let jump = 2 + (state >> pos & 1) as usize;
+                    state ^= 1 << pos;
+                    pos += jump;
+                    steps += 1;
+                }
+                table.push(state | steps << 16 | ((pos - CHUNK) as u32) << 24);
+            }
+        }
+        table
+    })
+}
+
+fn is_settled(offset: i32) -> bool {
+    offset == 2 || offset == 3
+}
+
+/// The number of steps to escape `offsets` under the part 2 rule.
+///
+/// Offsets only ever move towards 2 and 3, so they are stored as `i32` if they all fit in one.
+/// Mazes with larger offsets are left to `JumpMaze`.
+pub fn escape_strange_maze(offsets: &[isize]) -> usize {
+    let narrow = offsets
+        .iter()
+        .map(|&offset| i32::try_from(offset))
+        .collect::<Result<Vec<_>, _>>();
+    let mut offsets = match narrow {
+        Ok(offsets) => offsets,
+        Err(_) => {
+            let mut maze = JumpMaze::new(offsets.to_vec(), JumpMaze::strange_increment);
+            maze.run();
+            return maze.steps();
+        }
+    };
+
+    let table = chunk_table();
+    let len = offsets.len();
+
+    // The number of unsettled cells in each chunk, and the packed bits of the settled chunks at
+    // the start of the maze. Once a chunk is packed, `offsets` is no longer updated for it.
+    let mut unsettled = offsets
+        .chunks(CHUNK)
+        .map(|chunk| chunk.iter().filter(|&&offset| !is_settled(offset)).count())
+        .collect::<Vec<_>>();
+    let mut settled: Vec<u32> = vec![];
+
+    let mut pc = 0;
+    let mut steps = 0;
+
+    loop {
+        // Pack every complete chunk that has fully settled on to the end of the prefix.
+        while settled.len() < len / CHUNK && unsettled[settled.len()] == 0 {
+            let start = settled.len() * CHUNK;
+            let bits = offsets[start..start + CHUNK]
+                .iter()
+                .enumerate()
+                .fold(0, |bits, (i, &offset)| bits | ((offset == 3) as u32) << i);
+            settled.push(bits);
+        }
+
+        if pc < settled.len() * CHUNK {
+            // Walking forwards through the prefix always enters the next chunk, so only the
+            // overshoot carries from one lookup to the next.
+            let (mut chunk, mut entry) = (pc / CHUNK, pc % CHUNK);
+
+            while chunk < settled.len() {
+                let walk = table[(settled[chunk] as usize) * CHUNK + entry];
+
+                settled[chunk] = walk & ((1 << CHUNK) - 1);
+                steps += (walk >> 16 & 0xff) as usize;
+                entry = (walk >> 24) as usize;
+                chunk += 1;
+            }
+            pc = chunk * CHUNK + entry;
+        }
+        if pc >= len {
+            return steps;
+        }
+
+        let offset = offsets[pc];
+        let next = if offset >= 3 { offset - 1 } else { offset + 1 };
+        offsets[pc] = next;
+        if is_settled(next) && !is_settled(offset) {
+            unsettled[pc / CHUNK] -= 1;
+        }
+        steps += 1;
+
+        let next_pc = pc as isize + offset as isize;
+        if next_pc < 0 {
+            return steps;
+        }
+        pc = next_pc as usize;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(offsets: &[isize]) -> usize {
+        let mut maze = JumpMaze::new(offsets.to_vec(), JumpMaze::strange_increment);
+        maze.run();
+        maze.steps()
+    }
+
+    #[test]
+    fn sample() {
+        assert_eq!(10, escape_strange_maze(&[0, 3, 0, 1, -3]));
+        assert_eq!(0, escape_strange_maze(&[]));
+        assert_eq!(1, escape_strange_maze(&[-1]));
+    }
+
+    #[test]
+    fn offsets_wider_than_i32() {
+        let offsets = [2, 1 << 40, -(1 << 40), 0, -4];
+        assert_eq!(reference(&offsets), escape_strange_maze(&offsets));
+        assert_eq!(1, escape_strange_maze(&[1 << 32]));
+        assert_eq!(1, escape_strange_maze(&[-(1 << 32)]));
+    }
+
+    #[test]
+    fn matches_jump_maze_on_random_mazes() {
+        // A small linear congruential generator keeps the mazes the same on every run.
+        let mut seed: u64 = 2017;
+        let mut random = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (seed >> 33) % bound
+        };
+
+        for _ in 0..200 {
+            let len = random(300) as usize;
+            let spread = 1 + random(40) as isize;
+            let offsets = (0..len)
+                .map(|i| {
+                    let offset = random(2 * spread as u64 + 1) as isize - spread;
+                    // Keep some jumps pointing back at the start of the maze.
+                    offset.max(-(i as isize) - 2)
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(reference(&offsets), escape_strange_maze(&offsets), "{:?}", offsets);
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::fmt;
+use std::io::{self, Write};
+
+/// Which end of the maze a jump left through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    /// Past the last offset.
+    Forwards,
+    /// Before the first offset.
+    Backwards,
+}
+
+/// Why `JumpMaze::run` stopped. Each variant holds the total number of steps taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Escaped { direction: Escape, steps: usize },
+    /// The step limit was reached before escaping.
+    StepLimit { steps: usize },
+    /// The maze returned to an earlier state, so it will repeat forever and never escape.
+    Trapped { steps: usize },
+}
+
+/// A list of jump offsets and a program counter. Every step jumps by the offset at the program
+/// counter and then changes that offset using the maze's rule.
+#[derive(Debug, Clone)]
+pub struct JumpMaze {
+    pc: usize,
+    offsets: Vec<isize>,
+    rule: fn(isize) -> isize,
+    steps: usize,
+    escaped: Option<Escape>,
+    step_limit: Option<usize>,
+    detect_loops: bool,
+}
+
+impl JumpMaze {
+    /// A maze starting at the first offset, with no step limit and loop detection turned on. An
+    /// empty maze has already been escaped.
+    pub fn new(offsets: Vec<isize>, rule: fn(isize) -> isize) -> JumpMaze {
+        let escaped = if offsets.is_empty() {
+            Some(Escape::Forwards)
+        } else {
+            None
+        };
+
+        JumpMaze {
+            pc: 0,
+            offsets,
+            rule,
+            steps: 0,
+            escaped,
+            step_limit: None,
+            detect_loops: true,
+        }
+    }
+
+    /// The rule from part 1: every offset increases by one after it is used.
+    pub fn increment(offset: isize) -> isize {
+        offset + 1
+    }
+
+    /// The rule from part 2: offsets of three or more decrease by one, and others increase by one.
+    pub fn strange_increment(offset: isize) -> isize {
+        if offset >= 3 {
+            offset - 1
+        } else {
+            offset + 1
+        }
+    }
+
+    /// Stop `run` once this many steps have been taken in total.
+    pub fn set_step_limit(&mut self, step_limit: Option<usize>) {
+        self.step_limit = step_limit;
+    }
+
+    /// Whether `run` watches for repeated states. Without it, a trapped maze with no step limit
+    /// runs forever.
+    pub fn set_loop_detection(&mut self, detect_loops: bool) {
+        self.detect_loops = detect_loops;
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn offsets(&self) -> &[isize] {
+        &self.offsets
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn escaped(&self) -> Option<Escape> {
+        self.escaped
+    }
+
+    /// Take a single jump, returning the direction if the maze has been escaped.
+    pub fn step(&mut self) -> Option<Escape> {
+        if self.escaped.is_some() {
+            return self.escaped;
+        }
+
+        let offset = self.offsets[self.pc];
+        let next_pc = self.pc as isize + offset;
+        self.offsets[self.pc] = (self.rule)(offset);
+        self.steps += 1;
+
+        if next_pc < 0 {
+            self.escaped = Some(Escape::Backwards);
+        } else if next_pc as usize >= self.offsets.len() {
+            self.escaped = Some(Escape::Forwards);
+        } else {
+            self.pc = next_pc as usize;
+        }
+        self.escaped
+    }
+
+    /// Jump until the maze is escaped, the step limit is reached or a loop is found.
+    pub fn run(&mut self) -> Outcome {
+        self.run_observed(|_| Ok(())).unwrap()
+    }
+
+    /// Run the maze, writing the state before the first step and after every step to `writer`.
+    pub fn trace<W: Write>(&mut self, writer: &mut W) -> io::Result<Outcome> {
+        writeln!(writer, "{}", self)?;
+        let outcome = self.run_observed(|maze| writeln!(writer, "{}", maze))?;
+
+        match outcome {
+            Outcome::Escaped { direction, steps } => {
+                writeln!(writer, "Escaped {:?} after {} steps", direction, steps)
+            }
+            Outcome::StepLimit { steps } => writeln!(writer, "Stopped after {} steps", steps),
+            Outcome::Trapped { steps } => writeln!(writer, "Trapped after {} steps", steps),
+        }?;
+        Ok(outcome)
+    }
+
+    fn run_observed<F>(&mut self, mut observe: F) -> io::Result<Outcome>
+    where
+        F: FnMut(&JumpMaze) -> io::Result<()>,
+    {
+        // Brent's algorithm: compare against a saved state that is replaced at every power of two
+        // steps, so a loop is found within twice its length of entering it.
+        let mut saved = (self.pc, self.offsets.clone());
+        let mut power = 1;
+        let mut lambda = 0;
+
+        loop {
+            if let Some(direction) = self.escaped {
+                return Ok(Outcome::Escaped {
+                    direction,
+                    steps: self.steps,
+                });
+            }
+            if self.step_limit.is_some_and(|limit| self.steps >= limit) {
+                return Ok(Outcome::StepLimit { steps: self.steps });
+            }
+
+            self.step();
+            observe(self)?;
+
+            if self.detect_loops && self.escaped.is_none() {
+                if self.pc == saved.0 && self.offsets == saved.1 {
+                    return Ok(Outcome::Trapped { steps: self.steps });
+                }
+
+                lambda += 1;
+                if lambda == power {
+                    saved.0 = self.pc;
+                    saved.1.copy_from_slice(&self.offsets);
+                    power *= 2;
+                    lambda = 0;
+                }
+            }
+        }
+    }
+}
+
+/// The offsets separated by spaces, with the one at the program counter in brackets.
+impl fmt::Display for JumpMaze {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, offset) in self.offsets.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            if i == self.pc && self.escaped.is_none() {
+                write!(f, "({})", offset)?;
+            } else {
+                write!(f, "{}", offset)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes() {
+        let mut maze = JumpMaze::new(vec![0, 3, 0, 1, -3], JumpMaze::increment);
+        assert_eq!(
+            Outcome::Escaped {
+                direction: Escape::Forwards,
+                steps: 5,
+            },
+            maze.run()
+        );
+        assert_eq!(&[2, 5, 0, 1, -2], maze.offsets());
+
+        let mut maze = JumpMaze::new(vec![1, -2, 0], JumpMaze::increment);
+        assert_eq!(None, maze.step());
+        assert_eq!(1, maze.pc());
+        assert_eq!(Some(Escape::Backwards), maze.step());
+        assert_eq!(Some(Escape::Backwards), maze.step());
+        assert_eq!(2, maze.steps());
+
+        let mut maze = JumpMaze::new(vec![], JumpMaze::increment);
+        assert_eq!(
+            Outcome::Escaped {
+                direction: Escape::Forwards,
+                steps: 0,
+            },
+            maze.run()
+        );
+    }
+
+    #[test]
+    fn step_limit() {
+        let mut maze = JumpMaze::new(vec![0, 3, 0, 1, -3], JumpMaze::strange_increment);
+        maze.set_step_limit(Some(4));
+
+        assert_eq!(Outcome::StepLimit { steps: 4 }, maze.run());
+        assert_eq!(1, maze.pc());
+
+        maze.set_step_limit(None);
+        assert_eq!(
+            Outcome::Escaped {
+                direction: Escape::Forwards,
+                steps: 10,
+            },
+            maze.run()
+        );
+    }
+
+    #[test]
+    fn trapped() {
+        fn negate(offset: isize) -> isize {
+            -offset
+        }
+
+        let mut maze = JumpMaze::new(vec![2, 0, -1], negate);
+        assert_eq!(Outcome::Trapped { steps: 4 }, maze.run());
+
+        let mut maze = JumpMaze::new(vec![0], |offset| offset);
+        assert_eq!(Outcome::Trapped { steps: 1 }, maze.run());
+    }
+
+    #[test]
+    fn trace() {
+        let mut maze = JumpMaze::new(vec![0, 3, 0, 1, -3], JumpMaze::increment);
+        let mut output = vec![];
+        maze.trace(&mut output).unwrap();
+
+        let expected = "\
+(0) 3 0 1 -3
+(1) 3 0 1 -3
+2 (3) 0 1 -3
+2 4 0 1 (-3)
+2 (4) 0 1 -2
+2 5 0 1 -2
+Escaped Forwards after 5 steps
+";
+        assert_eq!(expected, String::from_utf8(output).unwrap());
+    }
+}
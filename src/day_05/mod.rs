@@ -1,38 +1,10 @@
 use super::Day;
 
-struct JumpIncrementIter {
-    pc: usize,
-    len: usize,
-    offsets: Vec<isize>,
-    increment_fn: fn(isize) -> isize,
-}
-
-impl JumpIncrementIter {
-    pub fn new(offsets: Vec<isize>, increment_fn: fn(isize) -> isize) -> JumpIncrementIter {
-        JumpIncrementIter {
-            pc: 0,
-            len: offsets.len(),
-            offsets,
-            increment_fn,
-        }
-    }
-}
+mod fast_strange;
+mod jump_maze;
 
-impl Iterator for JumpIncrementIter {
-    type Item = ();
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let ref mut offset = self.offsets[self.pc];
-        let next_pc = self.pc as isize + *offset;
-        if next_pc < self.len as isize && next_pc >= 0 {
-            *offset = (self.increment_fn)(*offset);
-            self.pc = next_pc as usize;
-            Some(())
-        } else {
-            None
-        }
-    }
-}
+pub use self::fast_strange::escape_strange_maze;
+pub use self::jump_maze::{Escape, JumpMaze, Outcome};
 
 /// [Day 05](https://adventofcode.com/2017/day/5) - Calculate how many steps are needed to exit
 /// a series of consecutive jump instructions
@@ -40,6 +12,17 @@ pub struct Day05 {
     offsets: Vec<isize>,
 }
 
+impl Day05 {
+    fn steps_to_escape(&self, rule: fn(isize) -> isize) -> usize {
+        let mut maze = JumpMaze::new(self.offsets.clone(), rule);
+
+        match maze.run() {
+            Outcome::Escaped { steps, .. } => steps,
+            outcome => panic!("Failed to escape the maze: {:?}", outcome),
+        }
+    }
+}
+
 impl<'a> Day<'a> for Day05 {
     const NUM: u32 = 5;
     type Output1 = usize;
@@ -52,16 +35,11 @@ impl<'a> Day<'a> for Day05 {
     }
 
     fn part_1(&self) -> usize {
-        1 + JumpIncrementIter::new(self.offsets.clone(), |offset| offset + 1).count()
+        self.steps_to_escape(JumpMaze::increment)
     }
 
     fn part_2(&self) -> usize {
-        1 +
-            JumpIncrementIter::new(self.offsets.clone(), |offset| if offset >= 3 {
-                offset - 1
-            } else {
-                offset + 1
-            }).count()
+        escape_strange_maze(&self.offsets)
     }
 }
 
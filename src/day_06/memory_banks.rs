@@ -0,0 +1,201 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::Hash;
+use std::iter;
+use std::mem;
+
+use super::super::util::cycle::{self, Cycle};
+
+/// An unsigned integer type used to count the blocks in a memory bank.
+pub trait BlockCount: Copy + Eq + Ord + Hash + fmt::Debug + fmt::Display {
+    fn zero() -> Self;
+    fn to_usize(self) -> usize;
+    /// Add `blocks`, or `None` if the result does not fit.
+    fn checked_add_usize(self, blocks: usize) -> Option<Self>;
+}
+
+macro_rules! impl_block_count {
+    ($($ty:ty),+) => ($(
+        impl BlockCount for $ty {
+            fn zero() -> Self {
+                0
+            }
+
+            fn to_usize(self) -> usize {
+                usize::try_from(self).expect("Block count does not fit in a usize")
+            }
+
+            fn checked_add_usize(self, blocks: usize) -> Option<Self> {
+                <$ty>::try_from(blocks).ok().and_then(|blocks| self.checked_add(blocks))
+            }
+        }
+    )+)
+}
+
+impl_block_count!(u8, u16, u32, u64, usize);
+
+/// A circular list of memory banks, each holding some number of blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MemoryBanks<B> {
+    banks: Vec<B>,
+}
+
+/// Every state seen while reallocating until a state repeats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History<B> {
+    /// The `cycle.mu + cycle.lambda` distinct states, starting with the initial state.
+    pub states: Vec<MemoryBanks<B>>,
+    pub cycle: Cycle,
+}
+
+impl<B> History<B> {
+    /// The states that repeat forever, starting with the first repeated state.
+    pub fn loop_states(&self) -> &[MemoryBanks<B>] {
+        &self.states[self.cycle.mu..]
+    }
+}
+
+impl<B: BlockCount> MemoryBanks<B> {
+    pub fn new(banks: Vec<B>) -> MemoryBanks<B> {
+        MemoryBanks { banks }
+    }
+
+    pub fn banks(&self) -> &[B] {
+        &self.banks
+    }
+
+    /// The index and size of the bank with the most blocks, choosing the lowest index on ties.
+    /// Panics if there are no banks.
+    pub fn fullest(&self) -> (usize, B) {
+        let (index, &blocks) = self.banks
+            .iter()
+            .enumerate()
+            .max_by(|&(i, blocks1), &(j, blocks2)| blocks1.cmp(blocks2).then(j.cmp(&i)))
+            .expect("There are no memory banks");
+        (index, blocks)
+    }
+
+    /// Empty the fullest bank and hand its blocks out one at a time to the following banks,
+    /// wrapping around. Every bank gets an equal share at once, and then the next
+    /// `blocks % banks` banks get one more. Panics if a bank overflows `B`.
+    pub fn redistribute(&self) -> MemoryBanks<B> {
+        let len = self.banks.len();
+        let (index, blocks) = self.fullest();
+        let blocks = blocks.to_usize();
+        let (share, remainder) = (blocks / len, blocks % len);
+
+        let mut banks = self.banks.clone();
+        banks[index] = B::zero();
+        for (i, bank) in banks.iter_mut().enumerate() {
+            let distance = (i + len - index - 1) % len;
+            let extra = if distance < remainder { 1 } else { 0 };
+
+            *bank = bank.checked_add_usize(share + extra)
+                .unwrap_or_else(|| panic!("Memory bank {} overflowed", i));
+        }
+
+        MemoryBanks { banks }
+    }
+
+    /// The endless sequence of states starting with this one.
+    pub fn states(&self) -> impl Iterator<Item = MemoryBanks<B>> {
+        let mut state = self.clone();
+
+        iter::repeat(()).map(move |_| {
+            let next = state.redistribute();
+            mem::replace(&mut state, next)
+        })
+    }
+
+    pub fn find_cycle(&self) -> Cycle {
+        cycle::find_cycle_hashing(self, MemoryBanks::redistribute)
+    }
+
+    /// Reallocate until a state repeats, keeping every state along the way.
+    pub fn history(&self) -> History<B> {
+        let (cycle, states) =
+            cycle::find_cycle_hashing_with_states(self, MemoryBanks::redistribute);
+
+        History { states, cycle }
+    }
+}
+
+/// The number of blocks in each bank, separated by spaces.
+impl<B: fmt::Display> fmt::Display for MemoryBanks<B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, bank) in self.banks.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", bank)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The original rule, moving one block at a time.
+    fn redistribute_one_at_a_time(banks: &[u64]) -> Vec<u64> {
+        let (mut index, blocks) = MemoryBanks::new(banks.to_vec()).fullest();
+        let mut output = banks.to_vec();
+
+        output[index] = 0;
+        for _ in 0..blocks {
+            index = (index + 1) % banks.len();
+            output[index] += 1;
+        }
+        output
+    }
+
+    #[test]
+    fn matches_one_block_at_a_time() {
+        let samples: &[&[u64]] = &[
+            &[0, 2, 7, 0],
+            &[9],
+            &[3, 0],
+            &[1, 20, 3, 4, 5],
+            &[0, 0, 0, 0, 1000, 2, 999],
+        ];
+
+        for &banks in samples {
+            assert_eq!(
+                redistribute_one_at_a_time(banks),
+                MemoryBanks::new(banks.to_vec()).redistribute().banks()
+            );
+        }
+    }
+
+    #[test]
+    fn wide_block_counts() {
+        let banks = MemoryBanks::new(vec![300u16, 200, 100]);
+
+        assert_eq!(&[100, 300, 200], banks.redistribute().banks());
+        assert_eq!("300 200 100", banks.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Memory bank 1 overflowed")]
+    fn overflow() {
+        MemoryBanks::new(vec![200u8, 200]).redistribute();
+    }
+
+    #[test]
+    fn history() {
+        let history = MemoryBanks::new(vec![0u8, 2, 7, 0]).history();
+        let states = history
+            .states
+            .iter()
+            .map(|state| state.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(Cycle { mu: 1, lambda: 4 }, history.cycle);
+        assert_eq!(
+            vec!["0 2 7 0", "2 4 1 2", "3 1 2 3", "0 2 3 4", "1 3 4 1"],
+            states
+        );
+        assert_eq!(&history.states[1..], history.loop_states());
+    }
+}
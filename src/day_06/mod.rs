@@ -1,49 +1,13 @@
 use super::Day;
 
-use std::collections::HashSet;
+mod memory_banks;
+
+pub use self::memory_banks::{BlockCount, History, MemoryBanks};
 
 /// [Day 06](https://adventofcode.com/2017/day/6) - Given a list of bins of various sizes, how many
 /// reallocations need to be performed to reach a previously seen state.
 pub struct Day06 {
-    initial_state: Vec<u8>,
-}
-
-impl Day06 {
-    fn find_most_full_bucket(buckets: &[u8]) -> (usize, u8) {
-        let (index, &val) = buckets
-            .iter()
-            .enumerate()
-            .max_by(|&(i, val1), &(j, val2)| val1.cmp(&val2).then(j.cmp(&i)))
-            .unwrap();
-        (index, val)
-    }
-
-    fn redistribute(buckets: &[u8]) -> Vec<u8> {
-        let buckets_len = buckets.len();
-        let (mut index, bucket_size) = Day06::find_most_full_bucket(buckets);
-        let mut output = buckets.to_owned();
-
-        output[index] = 0;
-        index = (index + 1) % buckets_len;
-
-        for _ in 0..bucket_size {
-            output[index] += 1;
-            index = (index + 1) % buckets_len;
-        }
-
-        output
-    }
-
-    fn find_fixed_point(initial_state: &[u8]) -> (Vec<u8>, usize) {
-        let mut previous_states = HashSet::new();
-        let mut state = initial_state.to_owned();
-
-        while previous_states.insert(state.clone()) {
-            state = Day06::redistribute(&state);
-        }
-
-        (state, previous_states.len())
-    }
+    initial_state: MemoryBanks<u32>,
 }
 
 impl<'a> Day<'a> for Day06 {
@@ -52,32 +16,35 @@ impl<'a> Day<'a> for Day06 {
     type Output2 = usize;
 
     fn from_str(input: &str) -> Day06 {
-        let initial_state = input
+        let banks = input
             .split_whitespace()
             .map(|word| word.parse().unwrap())
             .collect();
 
-        Day06 { initial_state }
+        Day06 {
+            initial_state: MemoryBanks::new(banks),
+        }
     }
 
     fn part_1(&self) -> usize {
-        Day06::find_fixed_point(&self.initial_state).1
+        let cycle = self.initial_state.find_cycle();
+        cycle.mu + cycle.lambda
     }
 
     fn part_2(&self) -> usize {
-        let fixed_point = Day06::find_fixed_point(&self.initial_state).0;
-        Day06::find_fixed_point(&fixed_point).1
+        self.initial_state.find_cycle().lambda
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::util::cycle::{self, Cycle};
 
     #[test]
     fn finds_index_of_most_full_bucket() {
         let samples = [
-            ([0, 2, 7, 0], (2, 7)),
+            ([0u32, 2, 7, 0], (2, 7)),
             ([2, 4, 1, 2], (1, 4)),
             ([3, 1, 2, 3], (0, 3)),
             ([0, 2, 3, 4], (3, 4)),
@@ -85,14 +52,14 @@ mod tests {
         ];
 
         for &(input, output) in samples.iter() {
-            assert_eq!(output, Day06::find_most_full_bucket(&input));
+            assert_eq!(output, MemoryBanks::new(input.to_vec()).fullest());
         }
     }
 
     #[test]
     fn redistributes_correctly() {
         let samples = [
-            (vec![0, 2, 7, 0], vec![2, 4, 1, 2]),
+            (vec![0u32, 2, 7, 0], vec![2, 4, 1, 2]),
             (vec![2, 4, 1, 2], vec![3, 1, 2, 3]),
             (vec![3, 1, 2, 3], vec![0, 2, 3, 4]),
             (vec![0, 2, 3, 4], vec![1, 3, 4, 1]),
@@ -100,16 +67,17 @@ mod tests {
         ];
 
         for &(ref input, ref output) in samples.iter() {
-            assert_eq!(*output, Day06::redistribute(&input[..]));
+            let banks = MemoryBanks::new(input.clone());
+            assert_eq!(&output[..], banks.redistribute().banks());
         }
     }
 
     #[test]
     fn parse_input() {
         let input = "0  2    7 0";
-        let output = vec![0, 2, 7, 0];
+        let output = [0, 2, 7, 0];
 
-        assert_eq!(output, Day06::from_str(&input).initial_state);
+        assert_eq!(&output, Day06::from_str(&input).initial_state.banks());
     }
 
     #[test]
@@ -121,11 +89,16 @@ mod tests {
     }
 
     #[test]
-    fn find_fixed_point() {
-        let input = vec![0, 2, 7, 0];
-        let output = vec![2, 4, 1, 2];
-
-        assert_eq!(output, Day06::find_fixed_point(&input).0);
+    fn find_cycle() {
+        let input = MemoryBanks::new(vec![0u32, 2, 7, 0]);
+        let output = MemoryBanks::new(vec![2, 4, 1, 2]);
+
+        let cycle = input.find_cycle();
+        assert_eq!(Cycle { mu: 1, lambda: 4 }, cycle);
+        assert_eq!(
+            output,
+            cycle::iterate(&input, MemoryBanks::redistribute, cycle.mu)
+        );
     }
 
     #[test]
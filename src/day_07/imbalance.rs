@@ -0,0 +1,268 @@
+use super::program::{ProgramId, ProgramTree};
+
+use std::fmt;
+
+/// Which child of an unbalanced program is in the wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OddChild {
+    /// The child whose total weight is the odd one out.
+    Found(ProgramId),
+    /// There are two balanced children, and either could be the one in the wrong.
+    Ambiguous,
+    /// No single child can be in the wrong: there are two children whose own children are both
+    /// unbalanced, or the weights of three or more children differ in more than one way.
+    Unfixable,
+}
+
+/// A program whose children do not all have the same total weight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImbalanceLevel {
+    pub parent: ProgramId,
+    /// Each child and its total weight.
+    pub weights: Vec<(ProgramId, u64)>,
+    pub odd: OddChild,
+}
+
+/// A single change of weight which balances the tower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeightFix {
+    pub program: ProgramId,
+    /// The weight the program should have.
+    pub weight: u64,
+    /// How far the weight has to change to get there.
+    pub adjustment: i64,
+}
+
+/// How the tower is unbalanced, found by following the odd child up from the root.
+#[derive(Debug, Clone)]
+pub struct ImbalanceReport<'t, 'a: 't> {
+    tree: &'t ProgramTree<'a>,
+    /// The programs from the root up to the faulty program. When the fault is ambiguous the path
+    /// stops at the program holding up the candidates.
+    pub path: Vec<ProgramId>,
+    /// One entry for each unbalanced program along the path.
+    pub levels: Vec<ImbalanceLevel>,
+    /// The ways to balance the tower: one, one for each candidate when the fault is ambiguous, or
+    /// none when no single weight can balance it.
+    pub fixes: Vec<WeightFix>,
+}
+
+impl<'t, 'a> ImbalanceReport<'t, 'a> {
+    pub fn new(
+        tree: &'t ProgramTree<'a>,
+        path: Vec<ProgramId>,
+        levels: Vec<ImbalanceLevel>,
+    ) -> ImbalanceReport<'t, 'a> {
+        let fixes = match levels.last() {
+            Some(level) => {
+                let candidates = match level.odd {
+                    OddChild::Found(odd) => vec![odd],
+                    OddChild::Ambiguous => level.weights.iter().map(|&(child, _)| child).collect(),
+                    OddChild::Unfixable => vec![],
+                };
+
+                candidates
+                    .into_iter()
+                    .filter_map(|candidate| {
+                        let &(_, expected) = level
+                            .weights
+                            .iter()
+                            .find(|&&(child, _)| child != candidate)?;
+                        let program = tree.get(candidate);
+                        let adjustment = expected as i64 - program.total_weight as i64;
+                        let weight = program.weight as i64 + adjustment;
+
+                        // A program can't weigh less than nothing.
+                        if weight < 0 {
+                            return None;
+                        }
+                        Some(WeightFix {
+                            program: candidate,
+                            weight: weight as u64,
+                            adjustment,
+                        })
+                    })
+                    .collect()
+            }
+            None => vec![],
+        };
+
+        ImbalanceReport {
+            tree,
+            path,
+            levels,
+            fixes,
+        }
+    }
+
+    /// Whether more than one program could be changed to balance the tower.
+    pub fn is_ambiguous(&self) -> bool {
+        self.fixes.len() > 1
+    }
+
+    /// The program that has the wrong weight, if there is only one candidate.
+    pub fn faulty(&self) -> Option<ProgramId> {
+        match self.fixes.as_slice() {
+            [fix] => Some(fix.program),
+            _ => None,
+        }
+    }
+}
+
+/// Each unbalanced level on its own line, listing the children's total weights with the odd one
+/// out marked by `*`, followed by the fixes.
+impl<'t, 'a> fmt::Display for ImbalanceReport<'t, 'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for level in self.levels.iter() {
+            let parent = self.tree.get(level.parent);
+            write!(f, "{} ({}):", parent.name, parent.weight)?;
+
+            for (i, &(child, total_weight)) in level.weights.iter().enumerate() {
+                let separator = if i > 0 { "," } else { "" };
+                let marker = if level.odd == OddChild::Found(child) { " *" } else { "" };
+                write!(
+                    f,
+                    "{} {} {}{}",
+                    separator,
+                    self.tree.get(child).name,
+                    total_weight,
+                    marker
+                )?;
+            }
+            writeln!(f)?;
+        }
+
+        if self.is_ambiguous() {
+            writeln!(f, "Either change:")?;
+        }
+        for fix in self.fixes.iter() {
+            let program = self.tree.get(fix.program);
+            writeln!(
+                f,
+                "{} weighs {} and should weigh {} ({:+})",
+                program.name, program.weight, fix.weight, fix.adjustment
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::tests::SAMPLE;
+
+
+    #[test]
+    fn sample() {
+        let tree = ProgramTree::parse(SAMPLE).unwrap();
+        let report = tree.imbalance().unwrap();
+        let id = |name| tree.id(name).unwrap();
+
+        assert_eq!(vec![id("tknk"), id("ugml")], report.path);
+        assert_eq!(
+            vec![
+                ImbalanceLevel {
+                    parent: id("tknk"),
+                    weights: vec![(id("ugml"), 251), (id("padx"), 243), (id("fwft"), 243)],
+                    odd: OddChild::Found(id("ugml")),
+                },
+            ],
+            report.levels
+        );
+        assert_eq!(
+            vec![
+                WeightFix {
+                    program: id("ugml"),
+                    weight: 60,
+                    adjustment: -8,
+                },
+            ],
+            report.fixes
+        );
+        assert_eq!(Some(id("ugml")), report.faulty());
+        assert_eq!(
+            "tknk (41): ugml 251 *, padx 243, fwft 243\nugml weighs 68 and should weigh 60 (-8)\n",
+            report.to_string()
+        );
+    }
+
+    #[test]
+    fn two_children() {
+        // b's children are unbalanced, so the fault must be above b rather than in c.
+        let input = "a (1) -> b, c\nb (5) -> d, e, f\nc (11)\nd (1)\ne (1)\nf (2)";
+        let tree = ProgramTree::parse(input).unwrap();
+        let report = tree.imbalance().unwrap();
+        let names = report
+            .path
+            .iter()
+            .map(|&id| tree.get(id).name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["a", "b", "f"], names);
+        assert_eq!(2, report.levels.len());
+        assert_eq!(Some(tree.id("f").unwrap()), report.faulty());
+    }
+
+    #[test]
+    fn ambiguous() {
+        let tree = ProgramTree::parse("a (1) -> b, c\nb (2)\nc (5)").unwrap();
+        let report = tree.imbalance().unwrap();
+
+        assert!(report.is_ambiguous());
+        assert_eq!(None, report.faulty());
+        assert_eq!(vec![tree.id("a").unwrap()], report.path);
+        assert_eq!(OddChild::Ambiguous, report.levels[0].odd);
+        let expected = "\
+a (1): b 2, c 5
+Either change:
+b weighs 2 and should weigh 5 (+3)
+c weighs 5 and should weigh 2 (-3)
+";
+        assert_eq!(expected, report.to_string());
+    }
+
+    #[test]
+    fn distinct_sibling_weights() {
+        // No single child can change to match the other two.
+        let tree = ProgramTree::parse("r (1) -> a, b, c\na (1)\nb (2)\nc (3)").unwrap();
+        let report = tree.imbalance().unwrap();
+
+        assert_eq!(OddChild::Unfixable, report.levels[0].odd);
+        assert_eq!(vec![tree.id("r").unwrap()], report.path);
+        assert!(report.fixes.is_empty());
+        assert_eq!(None, report.faulty());
+        assert_eq!("r (1): a 1, b 2, c 3\n", report.to_string());
+    }
+
+    #[test]
+    fn both_children_unbalanced() {
+        // Reweighting b or c can't balance the programs above them as well.
+        let input = "a (1) -> b, c
+b (1) -> d, e, f
+c (1) -> g, h, i
+d (1)
+e (1)
+f (2)
+g (1)
+h (1)
+i (5)";
+        let tree = ProgramTree::parse(input).unwrap();
+        let report = tree.imbalance().unwrap();
+
+        assert_eq!(OddChild::Unfixable, report.levels[0].odd);
+        assert!(report.fixes.is_empty());
+        assert_eq!(None, report.faulty());
+        assert!(!tree.to_dot().contains("fillcolor"));
+    }
+
+    #[test]
+    fn ambiguous_without_negative_weights() {
+        // Only b can change, as c would have to weigh less than nothing.
+        let tree = ProgramTree::parse("a (1) -> b, c\nb (1)\nc (1) -> d\nd (8)").unwrap();
+        let report = tree.imbalance().unwrap();
+
+        assert!(!report.is_ambiguous());
+        assert_eq!(Some(tree.id("b").unwrap()), report.faulty());
+    }
+}
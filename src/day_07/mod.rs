@@ -1,47 +1,123 @@
+mod imbalance;
 mod parser;
 mod program;
 
+pub use self::imbalance::{ImbalanceLevel, ImbalanceReport, OddChild, WeightFix};
+pub use self::program::{Program, ProgramId, ProgramTree, TowerError};
+
 use super::Day;
 
 pub struct Day07<'a> {
-    program_tree: program::ProgramTree<'a>,
+    program_tree: ProgramTree<'a>,
 }
 
 impl<'a> Day<'a> for Day07<'a> {
     const NUM: u32 = 7;
     type Output1 = &'a str;
-    type Output2 = i64;
+    type Output2 = String;
 
     fn from_str(input: &'a str) -> Self {
-        let program_tree = program::ProgramTree::from_str(input);
+        let program_tree =
+            ProgramTree::parse(input).unwrap_or_else(|err| panic!("Invalid tower: {}", err));
         Day07 { program_tree }
     }
 
     fn part_1(&self) -> Self::Output1 {
-        self.program_tree.bottom_programs[0].borrow().name
-    }
-
-    fn part_2(&self) -> i64 {
-        for (_, prog) in self.program_tree.programs.iter() {
-            let program = prog.borrow();
-            if program.has_incorrect_weight() {
-                match program.parent {
-                    Some(ref parent) => {
-                        let siblings_weights = parent.borrow().childrens_weights();
-                        let my_total_weight = program.total_weight() as i64;
-                        let correct_total_weight = siblings_weights
-                            .into_iter()
-                            .filter(|weight| *weight as i64 != my_total_weight)
-                            .next()
-                            .unwrap() as i64;
-
-                        return correct_total_weight - my_total_weight + program.weight as i64;
-                    }
-                    None => unreachable!(),
-                }
+        self.program_tree.root().name
+    }
+
+    fn part_2(&self) -> Self::Output2 {
+        let report = match self.program_tree.imbalance() {
+            Some(report) => report,
+            None => return "balanced".to_owned(),
+        };
+
+        match report.fixes.as_slice() {
+            [] => "no single weight can balance the tower".to_owned(),
+            [fix] => fix.weight.to_string(),
+            fixes => {
+                let options = fixes
+                    .iter()
+                    .map(|fix| {
+                        let name = self.program_tree.get(fix.program).name;
+                        format!("{} = {}", name, fix.weight)
+                    })
+                    .collect::<Vec<_>>();
+                format!("ambiguous, either {}", options.join(" or "))
             }
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The example tower from the puzzle, shared with the tests of the submodules.
+    pub const SAMPLE: &str = "pbga (66)
+xhth (57)
+ebii (61)
+havc (66)
+ktlj (57)
+fwft (72) -> ktlj, cntj, xhth
+qoyq (66)
+padx (45) -> pbga, havc, qoyq
+tknk (41) -> ugml, padx, fwft
+jptl (61)
+ugml (68) -> gyxo, ebii, jptl
+gyxo (61)
+cntj (57)";
+
+    #[test]
+    fn sample() {
+        let day = Day07::from_str(SAMPLE);
+
+        assert_eq!("tknk", day.part_1());
+        assert_eq!("60", day.part_2());
+    }
+
+    #[test]
+    fn balanced() {
+        let day = Day07::from_str("a (1) -> b, c\nb (2)\nc (2)");
 
-        unreachable!()
+        assert_eq!("balanced", day.part_2());
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[should_panic(expected = "Invalid tower: program a holds up b, which is never listed")]
+    fn invalid_tower() {
+        Day07::from_str("a (1) -> b");
+    }
+
+    #[test]
+    fn ambiguous() {
+        let day = Day07::from_str("a (1) -> b, c\nb (2)\nc (3)");
+
+        assert_eq!("ambiguous, either b = 3 or c = 2", day.part_2());
+    }
+
+    #[test]
+    fn no_single_fix() {
+        let day = Day07::from_str("r (1) -> a, b, c\na (1)\nb (2)\nc (3)");
+        assert_eq!("no single weight can balance the tower", day.part_2());
+
+        // One child is unique, but the rest don't agree on a weight either.
+        let day = Day07::from_str("r (1) -> a, b, c, d, e\na (1)\nb (2)\nc (2)\nd (3)\ne (3)");
+        assert_eq!("no single weight can balance the tower", day.part_2());
+    }
+
+    #[test]
+    fn two_children() {
+        // The root only has two children, so the heavier side is found by looking further up.
+        let day = Day07::from_str(
+            "a (1) -> b, c
+b (5) -> d, e, f
+c (11)
+d (1)
+e (1)
+f (2)",
+        );
+
+        assert_eq!("1", day.part_2());
+    }
+}
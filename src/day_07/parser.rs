@@ -19,7 +19,7 @@ named!(parse_weight(&str) -> u64,
     delimited!(tag!("("), map_res!(take_while_s!(is_digit), FromStr::from_str), tag!(")"))
 );
 
-named!(program(&str) -> (Program, Vec<&str>), do_parse!(
+named!(pub program(&str) -> (Program, Vec<&str>), do_parse!(
     name: take_until_s!(" ") >>
     tag!(" ") >>
     weight: parse_weight >>
@@ -27,9 +27,6 @@ named!(program(&str) -> (Program, Vec<&str>), do_parse!(
     ((Program::new(name, weight), children.unwrap_or(vec![])))
 ));
 
-named!(pub many_programs(&str) -> Vec<(Program, Vec<&str>)>,
-    many1!(ws!(program)));
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,168 +1,330 @@
+use super::imbalance::{ImbalanceLevel, ImbalanceReport, OddChild};
 use super::parser;
 
-
-use std::fmt;
+use nom::IResult;
 use std::collections::HashMap;
-use std::rc::Rc;
-use std::cell::RefCell;
-use std::cmp;
+use std::fmt;
 
-// Rc = Reference counted pointer
-// RefCell = A mutable memory location on the heap with borrow rules checked dynamically.
-//           Borrows are done by reference rather than by value.
-type ProgramRef<'a> = Rc<RefCell<Program<'a>>>;
+/// The index of a program in its `ProgramTree`.
+pub type ProgramId = usize;
 
-#[derive(Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Program<'a> {
     pub name: &'a str,
     pub weight: u64,
-    pub children: Vec<ProgramRef<'a>>,
-    pub parent: Option<ProgramRef<'a>>,
+    pub children: Vec<ProgramId>,
+    pub parent: Option<ProgramId>,
+    /// The weight of this program and everything it holds up.
+    pub total_weight: u64,
 }
 
-
 impl<'a> Program<'a> {
     pub fn new(name: &'a str, weight: u64) -> Program<'a> {
         Program {
-            name: name,
-            weight: weight,
+            name,
+            weight,
             children: vec![],
             parent: None,
+            total_weight: weight,
         }
     }
+}
 
-    pub fn total_weight(&self) -> u64 {
-        self.weight +
-            self.children
-                .iter()
-                .map(|child| child.borrow().total_weight())
-                .sum::<u64>()
-    }
-
-    fn add_child(&mut self, child: ProgramRef<'a>) {
-        self.children.push(child);
-        self.children.sort();
-    }
+/// A problem with the list of programs: a line that does not parse, or programs that do not
+/// stack up into a single tower.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TowerError<'a> {
+    /// The given line could not be parsed.
+    Syntax(&'a str),
+    /// Two programs have the same name.
+    DuplicateName(&'a str),
+    /// A program holds up a program which is never listed.
+    UnknownChild { parent: &'a str, child: &'a str },
+    /// A program is held up by more than one program.
+    MultipleParents { child: &'a str, parents: [&'a str; 2] },
+    /// The programs hold each other up in a loop, each holding up the next.
+    Cycle(Vec<&'a str>),
+    /// More than one program is not held up by anything.
+    MultipleRoots(Vec<&'a str>),
+}
 
-    pub fn childrens_weights(&self) -> Vec<u64> {
-        let mut all_childrens_weights = self.children
-            .iter()
-            .map(|child| child.borrow().total_weight())
-            .collect::<Vec<_>>();
-        all_childrens_weights.dedup();
-        all_childrens_weights
-    }
-
-    pub fn has_incorrect_weight(&self) -> bool {
-        match self.parent {
-            None => false,
-            Some(ref parent) => {
-                let all_siblings_weight = parent.borrow().childrens_weights();
-                if all_siblings_weight.len() == 1 {
-                    return false;
-                } else {
-                    let my_weight = self.total_weight();
-
-                    let instances_of_my_weight = all_siblings_weight
-                        .iter()
-                        .filter(|&&weight| weight == my_weight)
-                        .count();
-
-                    instances_of_my_weight == 1
-                }
+impl<'a> fmt::Display for TowerError<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TowerError::Syntax(line) => write!(f, "unable to parse tower at `{}`", line),
+            TowerError::DuplicateName(name) => {
+                write!(f, "program {} is listed more than once", name)
+            }
+            TowerError::UnknownChild { parent, child } => {
+                write!(f, "program {} holds up {}, which is never listed", parent, child)
+            }
+            TowerError::MultipleParents { child, parents } => write!(
+                f,
+                "program {} is held up by both {} and {}",
+                child, parents[0], parents[1]
+            ),
+            TowerError::Cycle(ref names) => write!(
+                f,
+                "programs hold each other up: {} -> {}",
+                names.join(" -> "),
+                names[0]
+            ),
+            TowerError::MultipleRoots(ref names) => {
+                write!(f, "more than one program is at the bottom: {}", names.join(", "))
             }
         }
     }
 }
 
-#[derive(PartialEq, Eq)]
+/// A tower of programs stored in a single `Vec`, with parents and children referred to by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProgramTree<'a> {
-    pub programs: HashMap<&'a str, ProgramRef<'a>>,
-    pub bottom_programs: Vec<ProgramRef<'a>>,
+    programs: Vec<Program<'a>>,
+    ids: HashMap<&'a str, ProgramId>,
+    root: ProgramId,
 }
 
 impl<'a> ProgramTree<'a> {
-    pub fn from_str(input: &'a str) -> ProgramTree<'a> {
-        let (unused, parse_results) = parser::many_programs(input).unwrap();
-        assert_eq!("", unused);
-
-        let mut parent_children_names = HashMap::new();
-        let mut programs = HashMap::new();
-
-        for (program, children_names) in parse_results.into_iter() {
-            parent_children_names.insert(program.name, children_names);
-            programs.insert(program.name, Rc::new(RefCell::new(program)));
+    /// Parse a tower, checking that a single program at the bottom holds up every other program
+    /// exactly once.
+    pub fn parse(input: &'a str) -> Result<ProgramTree<'a>, TowerError<'a>> {
+        let mut programs = vec![];
+        let mut ids = HashMap::new();
+        let mut children_names = vec![];
+
+        for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (program, names) = match parser::program(line) {
+                IResult::Done("", parsed) => parsed,
+                _ => return Err(TowerError::Syntax(line)),
+            };
+
+            if ids.insert(program.name, programs.len()).is_some() {
+                return Err(TowerError::DuplicateName(program.name));
+            }
+            programs.push(program);
+            children_names.push(names);
+        }
+        if programs.is_empty() {
+            return Err(TowerError::Syntax(""));
         }
 
-        for (parent_name, children_names) in parent_children_names.into_iter() {
-            for child_name in children_names.into_iter() {
-                let ref child = programs[&child_name];
-                let ref parent = programs[&parent_name];
-
-                child.borrow_mut().parent = Some(parent.clone());
-                parent.borrow_mut().add_child(child.clone());
+        for (parent, names) in children_names.into_iter().enumerate() {
+            for name in names.into_iter() {
+                let child = *ids.get(name).ok_or(TowerError::UnknownChild {
+                    parent: programs[parent].name,
+                    child: name,
+                })?;
+
+                if let Some(other) = programs[child].parent {
+                    return Err(TowerError::MultipleParents {
+                        child: name,
+                        parents: [programs[other].name, programs[parent].name],
+                    });
+                }
+                programs[child].parent = Some(parent);
+                programs[parent].children.push(child);
             }
         }
 
-        let bottom_programs = programs
-            .iter()
-            .filter(|&(_, program)| program.borrow().parent.is_none())
-            .map(|(_, program)| program.clone())
-            .collect();
+        let roots = (0..programs.len())
+            .filter(|&id| programs[id].parent.is_none())
+            .collect::<Vec<_>>();
+
+        // Every program has at most one parent, so anything that cannot be reached from a root
+        // is held up by a loop.
+        let reached = compute_total_weights(&mut programs, &roots);
+        if let Some(start) = reached.iter().position(|&reached| !reached) {
+            return Err(TowerError::Cycle(find_cycle(&programs, start)));
+        }
+        if roots.len() > 1 {
+            let names = roots.iter().map(|&id| programs[id].name).collect();
+            return Err(TowerError::MultipleRoots(names));
+        }
 
-        let output = ProgramTree {
+        Ok(ProgramTree {
             programs,
-            bottom_programs,
-        };
+            ids,
+            root: roots[0],
+        })
+    }
 
-        output
+    pub fn programs(&self) -> &[Program<'a>] {
+        &self.programs
+    }
+
+    pub fn get(&self, id: ProgramId) -> &Program<'a> {
+        &self.programs[id]
+    }
+
+    pub fn id(&self, name: &str) -> Option<ProgramId> {
+        self.ids.get(name).cloned()
+    }
+
+    /// The program at the bottom of the tower.
+    pub fn root(&self) -> &Program<'a> {
+        &self.programs[self.root]
     }
-}
 
-// We need to manually implement Debug, PartialOrd, Ord, and PartialEq for Program due
-// to the circular references between parents and children. If we try to derive these
-// traits automatically they will get stuck in an endless loop and smash the stack.
+    /// Whether every child of `id` has the same total weight.
+    fn is_balanced(&self, id: ProgramId) -> bool {
+        let children = &self.programs[id].children;
 
-impl<'a> fmt::Debug for Program<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        write!(f, "{} ({})", self.name, self.weight)?;
+        children.iter().all(|&child| {
+            self.programs[child].total_weight == self.programs[children[0]].total_weight
+        })
+    }
 
-        if self.children.len() > 0 {
-            write!(f, " -> {}", self.children[0].borrow().name)?;
+    /// The child of the unbalanced program `id` whose total weight differs from its siblings,
+    /// which all share one total weight. With only two children the odd one out is the child
+    /// whose own children are unbalanced: if neither is there is no telling, and if both are
+    /// then no single child can be fixed.
+    fn odd_child(&self, id: ProgramId) -> OddChild {
+        let children = &self.programs[id].children;
+        let weight_of = |child: ProgramId| self.programs[child].total_weight;
+
+        if children.len() == 2 {
+            return match (self.is_balanced(children[0]), self.is_balanced(children[1])) {
+                (true, true) => OddChild::Ambiguous,
+                (true, false) => OddChild::Found(children[1]),
+                (false, true) => OddChild::Found(children[0]),
+                (false, false) => OddChild::Unfixable,
+            };
         }
 
-        for child in self.children.iter().skip(1) {
-            write!(f, ", {}", child.borrow().name)?;
+        let odd = children.iter().cloned().find(|&child| {
+            children
+                .iter()
+                .filter(|&&sibling| weight_of(sibling) == weight_of(child))
+                .count() == 1
+        });
+        let odd = match odd {
+            Some(odd) => odd,
+            None => return OddChild::Unfixable,
+        };
+        let mut others = children
+            .iter()
+            .filter(|&&sibling| sibling != odd)
+            .map(|&sibling| weight_of(sibling));
+        let expected = others.next();
+
+        if others.all(|weight| Some(weight) == expected) {
+            OddChild::Found(odd)
+        } else {
+            OddChild::Unfixable
         }
-        Ok(())
     }
-}
 
-impl<'a> PartialOrd for Program<'a> {
-    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(self.name.cmp(&other.name).then(
-            self.weight.cmp(&other.weight),
-        ))
+    /// Follow the odd child up from the root until the children are balanced, or until it is
+    /// impossible to tell which child is wrong. `None` if the tower is balanced.
+    pub fn imbalance(&self) -> Option<ImbalanceReport<'_, 'a>> {
+        let mut path = vec![self.root];
+        let mut levels = vec![];
+        let mut id = self.root;
+
+        while !self.is_balanced(id) {
+            let odd = self.odd_child(id);
+            levels.push(ImbalanceLevel {
+                parent: id,
+                weights: self.programs[id]
+                    .children
+                    .iter()
+                    .map(|&child| (child, self.programs[child].total_weight))
+                    .collect(),
+                odd,
+            });
+
+            match odd {
+                OddChild::Found(child) => {
+                    path.push(child);
+                    id = child;
+                }
+                OddChild::Ambiguous | OddChild::Unfixable => break,
+            }
+        }
+
+        if levels.is_empty() {
+            None
+        } else {
+            Some(ImbalanceReport::new(self, path, levels))
+        }
     }
-}
 
-impl<'a> Ord for Program<'a> {
-    fn cmp(&self, other: &Self) -> cmp::Ordering {
-        self.name.cmp(&other.name).then(
-            self.weight.cmp(&other.weight),
-        )
+    /// The tower as a Graphviz digraph, with each program labelled by its name, own weight and
+    /// total weight. Programs that could be reweighted to balance the tower are filled in red.
+    pub fn to_dot(&self) -> String {
+        let faulty: Vec<ProgramId> = self.imbalance()
+            .map(|report| report.fixes.iter().map(|fix| fix.program).collect())
+            .unwrap_or_default();
+        let mut dot = String::from("digraph tower {\n    node [shape=box];\n");
+
+        for (id, program) in self.programs.iter().enumerate() {
+            let highlight = if faulty.contains(&id) {
+                ", style=filled, fillcolor=red"
+            } else {
+                ""
+            };
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\\n{} ({})\"{}];\n",
+                program.name, program.name, program.weight, program.total_weight, highlight
+            ));
+        }
+        for program in self.programs.iter() {
+            for &child in program.children.iter() {
+                dot.push_str(&format!(
+                    "    \"{}\" -> \"{}\";\n",
+                    program.name, self.programs[child].name
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
     }
 }
 
-impl<'a> PartialEq for Program<'a> {
-    fn eq(&self, other: &Self) -> bool {
-        let name = self.name == other.name;
-        let weight = self.weight == other.weight;
+/// Fill in every `total_weight` in a single post-order pass from the roots, returning which
+/// programs were reached.
+fn compute_total_weights(programs: &mut [Program], roots: &[ProgramId]) -> Vec<bool> {
+    let mut reached = vec![false; programs.len()];
+    let mut stack = roots.iter().map(|&root| (root, false)).collect::<Vec<_>>();
 
-        name && weight
+    while let Some((id, children_done)) = stack.pop() {
+        if children_done {
+            let children_weight = programs[id]
+                .children
+                .iter()
+                .map(|&child| programs[child].total_weight)
+                .sum::<u64>();
+            programs[id].total_weight = programs[id].weight + children_weight;
+        } else {
+            reached[id] = true;
+            stack.push((id, true));
+            stack.extend(programs[id].children.iter().map(|&child| (child, false)));
+        }
     }
+    reached
 }
 
+/// The names around the loop below `start`, which must not lead down to a root.
+fn find_cycle<'a>(programs: &[Program<'a>], start: ProgramId) -> Vec<&'a str> {
+    let parent = |id: ProgramId| programs[id].parent.expect("Program leads down to a root");
+    let mut seen = vec![false; programs.len()];
+    let mut id = start;
+
+    while !seen[id] {
+        seen[id] = true;
+        id = parent(id);
+    }
+
+    let mut cycle = vec![programs[id].name];
+    let mut next = parent(id);
+    while next != id {
+        cycle.push(programs[next].name);
+        next = parent(next);
+    }
+    // Following parents goes down the loop, so turn it around to list each holder first.
+    cycle[1..].reverse();
+    cycle
+}
 
 #[cfg(test)]
 mod tests {
@@ -174,24 +336,79 @@ mod tests {
         b (2)
         c (3) -> a, b";
 
-        let tree = ProgramTree::from_str(&input);
+        let tree = ProgramTree::parse(input).unwrap();
+        let (a, b, c) = (0, 1, 2);
 
-        let a = Rc::new(RefCell::new(Program::new("a", 1)));
-        let b = Rc::new(RefCell::new(Program::new("b", 2)));
-        let c = Rc::new(RefCell::new(Program::new("c", 3)));
+        assert_eq!(Some(c), tree.id("c"));
+        assert_eq!("c", tree.root().name);
+        assert_eq!(vec![a, b], tree.get(c).children);
+        assert_eq!(Some(c), tree.get(a).parent);
+        assert_eq!(Some(c), tree.get(b).parent);
+        assert_eq!(6, tree.get(c).total_weight);
+    }
 
-        a.borrow_mut().parent = Some(c.clone());
-        b.borrow_mut().parent = Some(c.clone());
-        c.borrow_mut().add_child(a.clone());
-        c.borrow_mut().add_child(b.clone());
+    #[test]
+    fn invalid_towers() {
+        assert_eq!(Err(TowerError::Syntax("b 2")), ProgramTree::parse("a (1)\nb 2"));
+        assert_eq!(Err(TowerError::Syntax("b (2")), ProgramTree::parse("a (1)\nb (2"));
+        assert_eq!(
+            Err(TowerError::Syntax("b (2) c")),
+            ProgramTree::parse("a (1)\nb (2) c\nc (3)")
+        );
+        assert_eq!(Err(TowerError::Syntax("")), ProgramTree::parse("\n"));
+        assert_eq!(
+            Err(TowerError::DuplicateName("a")),
+            ProgramTree::parse("a (1)\na (2)")
+        );
+        assert_eq!(
+            Err(TowerError::UnknownChild {
+                parent: "a",
+                child: "b",
+            }),
+            ProgramTree::parse("a (1) -> b")
+        );
+        assert_eq!(
+            Err(TowerError::MultipleParents {
+                child: "c",
+                parents: ["a", "b"],
+            }),
+            ProgramTree::parse("a (1) -> c\nb (1) -> c\nc (1)")
+        );
+        assert_eq!(
+            Err(TowerError::Cycle(vec!["b", "c", "d"])),
+            ProgramTree::parse("a (1)\nb (1) -> c\nc (1) -> d\nd (1) -> b")
+        );
+        assert_eq!(
+            Err(TowerError::Cycle(vec!["a"])),
+            ProgramTree::parse("a (1) -> a")
+        );
+        assert_eq!(
+            Err(TowerError::MultipleRoots(vec!["a", "b"])),
+            ProgramTree::parse("a (1)\nb (1)")
+        );
+    }
 
-        let mut expected_programs = HashMap::new();
-        expected_programs.insert("a", a.clone());
-        expected_programs.insert("b", b.clone());
-        expected_programs.insert("c", c.clone());
+    #[test]
+    fn to_dot() {
+        let tree = ProgramTree::parse("a (1) -> b, c, d\nb (2)\nc (2)\nd (3)").unwrap();
+        let expected = r#"digraph tower {
+    node [shape=box];
+    "a" [label="a\n1 (8)"];
+    "b" [label="b\n2 (2)"];
+    "c" [label="c\n2 (2)"];
+    "d" [label="d\n3 (3)", style=filled, fillcolor=red];
+    "a" -> "b";
+    "a" -> "c";
+    "a" -> "d";
+}
+"#;
 
-        assert_eq!(expected_programs, tree.programs);
-        assert!(!tree.bottom_programs.is_empty());
+        assert_eq!(expected, tree.to_dot());
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn tree_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<ProgramTree>();
+    }
+}
@@ -1,12 +1,5 @@
 use super::{Condition, Predicate, Instruction, OpCode};
-
-use std::str::FromStr;
-
-named!(parse_value(&str) -> i32, do_parse!(
-    negative: opt!(ws!(tag_s!("-"))) >>
-    value: ws!(map_res!(take_while_s!(|c: char| c.is_digit(10)), FromStr::from_str)) >>
-    (if negative.is_some() { -1 * value } else { value })
-));
+use super::super::util::parse_i32;
 
 named!(parse_predicate(&str) -> Predicate, do_parse!(
     cmp: alt!(tag_s!(">=") | tag_s!("<=") | tag_s!("==") | tag_s!("!=") | tag_s!(">") | tag_s!("<")) >>
@@ -34,7 +27,7 @@ named!(parse_condition(&str) -> Condition, do_parse!(
     tag_s!(" ") >>
     pred: call!(parse_predicate) >>
     tag_s!(" ") >>
-    value: call!(parse_value) >>
+    value: call!(parse_i32) >>
     (Condition {
         predicate: pred,
         register: register,
@@ -47,7 +40,7 @@ named!(pub parse_instruction(&str) -> Instruction, do_parse!(
     tag_s!(" ") >>
     op_code: parse_op_code >>
     tag_s!(" ") >>
-    value: call!(parse_value) >>
+    value: call!(parse_i32) >>
     condition: ws!(call!(parse_condition)) >>
     ( Instruction {
         condition,
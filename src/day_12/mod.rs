@@ -57,6 +57,33 @@ impl ProgramVillage {
 
         output
     }
+
+    /// The village as a Graphviz graph, with the villagers in each group sharing a colour.
+    pub fn to_dot(&self) -> String {
+        let groups = self.calculate_groups();
+        let mut dot = String::from("graph village {\n");
+        dot.push_str("    node [style=filled, colorscheme=set312];\n");
+
+        for (villager, group) in groups.into_iter().enumerate() {
+            dot.push_str(&format!("    {} [fillcolor={}];\n", villager, (group - 1) % 12 + 1));
+        }
+
+        let mut links = self.graph
+            .raw_edges()
+            .iter()
+            .map(|edge| {
+                let (from, to) = (self.graph[edge.source()], self.graph[edge.target()]);
+                (from.min(to), from.max(to))
+            })
+            .collect::<Vec<_>>();
+        links.sort();
+
+        for (from, to) in links.into_iter() {
+            dot.push_str(&format!("    {} -- {};\n", from, to));
+        }
+        dot.push_str("}\n");
+        dot
+    }
 }
 
 pub struct Day12 {
@@ -109,6 +136,23 @@ mod test {
         assert_eq!(groups, Day12::from_str(&INPUT).village.calculate_groups());
     }
 
+    #[test]
+    fn to_dot() {
+        let mut village = ProgramVillage::new(3);
+        village.populate_village("0 <-> 2\n1 <-> 1\n2 <-> 0");
+
+        let expected = "graph village {
+    node [style=filled, colorscheme=set312];
+    0 [fillcolor=1];
+    1 [fillcolor=2];
+    2 [fillcolor=1];
+    0 -- 2;
+    1 -- 1;
+}
+";
+        assert_eq!(expected, village.to_dot());
+    }
+
     #[test]
     fn part_2() {
         assert_eq!(2, Day12::from_str(&INPUT).part_2());
@@ -1,15 +1,13 @@
-use std::collections::HashMap;
-use std::fmt::Write;
-
+use super::super::util::grid::Grid;
 use super::super::util::knot_hash::KnotHash;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct DiskGrid {
-    grid: HashMap<(u8, u8), Status>,
+    grid: Grid<Status>,
     regions: usize,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 enum Status {
     Free,
     Used,
@@ -51,30 +49,13 @@ impl From<bool> for Status {
 
 impl DiskGrid {
     pub fn from_str(input: &str) -> DiskGrid {
-        let mut grid: HashMap<(u8, u8), Status> = HashMap::with_capacity(128 * 128);
+        let mut grid = Grid::new(128, 128, Status::Free);
         for j in 0..128 {
             let input_str = format!("{}-{}", input, j);
-            let bytes = KnotHash::hash_str(&input_str);
-
-            for i_major in 0..16 {
-                let byte = bytes[i_major];
-                let i_0 = byte & 0b00000001 == 0b00000001;
-                let i_1 = byte & 0b00000010 == 0b00000010;
-                let i_2 = byte & 0b00000100 == 0b00000100;
-                let i_3 = byte & 0b00001000 == 0b00001000;
-                let i_4 = byte & 0b00010000 == 0b00010000;
-                let i_5 = byte & 0b00100000 == 0b00100000;
-                let i_6 = byte & 0b01000000 == 0b01000000;
-                let i_7 = byte & 0b10000000 == 0b10000000;
-
-                grid.insert((i_major * 8 + 0, j), i_7.into());
-                grid.insert((i_major * 8 + 1, j), i_6.into());
-                grid.insert((i_major * 8 + 2, j), i_5.into());
-                grid.insert((i_major * 8 + 3, j), i_4.into());
-                grid.insert((i_major * 8 + 4, j), i_3.into());
-                grid.insert((i_major * 8 + 5, j), i_2.into());
-                grid.insert((i_major * 8 + 6, j), i_1.into());
-                grid.insert((i_major * 8 + 7, j), i_0.into());
+            let hash = KnotHash::hash_str(&input_str);
+
+            for (i, used) in hash.bits().enumerate() {
+                grid[(i, j)] = used.into();
             }
         }
 
@@ -88,24 +69,22 @@ impl DiskGrid {
             .count()
     }
 
-    pub fn to_str(&self, width: u8, height: u8) -> String {
-        let mut string_buffer = String::with_capacity(width as usize * height as usize);
-
-        for j in 0..height {
-            for i in 0..width {
-                write!(
-                    &mut string_buffer,
-                    "{}",
-                    if self.grid[&(i, j)].is_free() {
-                        '.'
-                    } else {
-                        '#'
-                    }
-                ).unwrap();
-            }
-            write!(&mut string_buffer, "\n").unwrap();
-        }
-        string_buffer
+    /// The top left `width` by `height` squares, with `#` for used and `.` for free. The size is
+    /// clamped to the grid.
+    pub fn to_str(&self, width: usize, height: usize) -> String {
+        let width = width.min(self.grid.width());
+        let height = height.min(self.grid.height());
+
+        (0..height)
+            .map(|j| {
+                let mut line = (0..width)
+                    .filter_map(|i| self.grid.get((i, j)))
+                    .map(|status| if status.is_free() { '.' } else { '#' })
+                    .collect::<String>();
+                line.push('\n');
+                line
+            })
+            .collect()
     }
 
     pub fn total_regions(&self) -> usize {
@@ -118,8 +97,8 @@ impl DiskGrid {
         for j in 0..128 {
             for i in 0..128 {
                 let pos = (i, j);
-                if self.grid[&pos].is_used() {
-                    self.grid.insert(pos, Status::Region(region));
+                if self.grid[pos].is_used() {
+                    self.grid[pos] = Status::Region(region);
                     self.update_neighbours(pos, region);
 
                     region += 1;
@@ -130,28 +109,21 @@ impl DiskGrid {
         self.regions = region;
     }
 
-    fn update_neighbours(&mut self, pos: (u8, u8), region: usize) {
+    fn update_neighbours(&mut self, pos: (usize, usize), region: usize) {
         let neighbours = self.used_neighbours(pos);
 
         for neighbour_pos in neighbours.iter() {
-            self.grid.insert(*neighbour_pos, Status::Region(region));
+            self.grid[*neighbour_pos] = Status::Region(region);
         }
         for neighbour_pos in neighbours.into_iter() {
             self.update_neighbours(neighbour_pos, region);
         }
     }
 
-    fn used_neighbours(&self, (x, y): (u8, u8)) -> Vec<(u8, u8)> {
-        let immediate_neighbours = vec![
-            (x.wrapping_sub(1), y),
-            (x + 1, y),
-            (x, y.wrapping_sub(1)),
-            (x, y + 1),
-        ];
-
-        immediate_neighbours
-            .into_iter()
-            .filter(|pos| self.grid.get(pos).unwrap_or(&Status::Free).is_used())
+    fn used_neighbours(&self, pos: (usize, usize)) -> Vec<(usize, usize)> {
+        self.grid
+            .neighbours_4(pos)
+            .filter(|&neighbour| self.grid[neighbour].is_used())
             .collect()
     }
 }
@@ -200,4 +172,14 @@ mod tests {
 
         assert_eq!(expected_output, grid.to_str(width, height));
     }
+
+    #[test]
+    fn to_str_clamps_to_grid() {
+        let grid = DiskGrid::from_str("flqrgnkx");
+        let output = grid.to_str(200, 3);
+
+        assert_eq!(grid.to_str(128, 3), output);
+        assert_eq!(vec![128; 3], output.lines().map(str::len).collect::<Vec<_>>());
+        assert_eq!(128, grid.to_str(1, 1000).lines().count());
+    }
 }
@@ -1,4 +1,5 @@
 use super::Day;
+use super::util::cycle;
 
 mod dancers;
 
@@ -24,29 +25,16 @@ impl<'a> Day<'a> for Day16<'a> {
     }
 
     fn part_2(&self) -> Self::Output2 {
-        let mut dancers = Dancers::new('p');
-        let starting_dancers = Dancers::new('p');
         let dance_moves = DanceMove::parse_many(self.input).unwrap().1;
-        let mut loop_size = 0;
-
-        while ((dancers != starting_dancers && loop_size != 0) || loop_size == 0)
-            && loop_size < 1_000_000_000_u64
-        {
-            dancers.dance_record(self.input);
-            loop_size += 1;
-        }
 
-        if loop_size < 1_000_000_000_u64 {
-            let remaning_iters = 1_000_000_000_u64 - loop_size;
-            let meaningful_iters = remaning_iters % loop_size;
-
-            for _ in 0..meaningful_iters {
+        cycle::state_after(
+            &Dancers::new('p'),
+            |dancers| {
+                let mut dancers = dancers.clone();
                 dancers.dance_all(&dance_moves);
-            }
-
-            dancers
-        } else {
-            dancers
-        }
+                dancers
+            },
+            1_000_000_000,
+        )
     }
 }
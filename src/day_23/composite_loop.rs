@@ -0,0 +1,281 @@
+use super::coprocessor::{Coprocessor, Instruction, Value};
+
+use std::fmt;
+
+/// The index of the first instruction of the loop body, reached once `b` and `c` have been set up.
+const LOOP_START: usize = 8;
+
+/// The number of steps the set up code is allowed to take before reaching the loop body.
+const MAX_SETUP_STEPS: usize = 100;
+
+/// The body of the program from `LOOP_START` onwards, for the given step between values of `b`.
+///
+/// For every `b` in `b, b + step, .., c` this checks whether any `d * e == b` for `2 <= d, e < b`
+/// and increments `h` if so, i.e. it counts the composite numbers in the range.
+fn expected_body(step: i64) -> Vec<Instruction> {
+    use self::Instruction::*;
+    use self::Value::*;
+
+    vec![
+        Set('f', Literal(1)),
+        Set('d', Literal(2)),
+        Set('e', Literal(2)),
+        Set('g', Register('d')),
+        Mul('g', Register('e')),
+        Sub('g', Register('b')),
+        Jnz(Register('g'), Literal(2)),
+        Set('f', Literal(0)),
+        Sub('e', Literal(-1)),
+        Set('g', Register('e')),
+        Sub('g', Register('b')),
+        Jnz(Register('g'), Literal(-8)),
+        Sub('d', Literal(-1)),
+        Set('g', Register('d')),
+        Sub('g', Register('b')),
+        Jnz(Register('g'), Literal(-13)),
+        Jnz(Register('f'), Literal(2)),
+        Sub('h', Literal(-1)),
+        Set('g', Register('b')),
+        Sub('g', Register('c')),
+        Jnz(Register('g'), Literal(2)),
+        Jnz(Literal(1), Literal(3)),
+        Sub('b', Literal(-step)),
+        Jnz(Literal(1), Literal(-23)),
+    ]
+}
+
+/// How a program differs from the composite counting loop that `CompositeLoop::recognise`
+/// looks for.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RecognitionError {
+    /// The program has a different number of instructions to the expected shape.
+    WrongLength(usize),
+    /// The set up code did not reach the start of the loop body.
+    SetupDidNotReachLoop,
+    /// An instruction of the loop body differs from the expected shape.
+    UnexpectedInstruction {
+        index: usize,
+        expected: Instruction,
+        found: Instruction,
+    },
+    /// Stepping from `b` by `step` never lands exactly on `c`, so the program would never halt.
+    NeverTerminates { b: i64, c: i64, step: i64 },
+}
+
+impl fmt::Display for RecognitionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RecognitionError::WrongLength(len) => write!(
+                f,
+                "expected a program of {} instructions, found {}",
+                LOOP_START + expected_body(0).len(),
+                len
+            ),
+            RecognitionError::SetupDidNotReachLoop => write!(
+                f,
+                "set up code did not reach instruction {} within {} steps",
+                LOOP_START,
+                MAX_SETUP_STEPS
+            ),
+            RecognitionError::UnexpectedInstruction {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "instruction {} is `{}`, expected `{}`",
+                index,
+                found,
+                expected
+            ),
+            RecognitionError::NeverTerminates { b, c, step } => write!(
+                f,
+                "stepping from b = {} by {} never reaches c = {}",
+                b,
+                step,
+                c
+            ),
+        }
+    }
+}
+
+/// The parameters of the loop, extracted from a recognised program, that counts the composite
+/// numbers in `start, start + step, .., end`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CompositeLoop {
+    pub start: i64,
+    pub end: i64,
+    pub step: i64,
+}
+
+fn is_composite(n: i64) -> bool {
+    n >= 4 && (2..).take_while(|d| d * d <= n).any(|d| n % d == 0)
+}
+
+impl CompositeLoop {
+    /// Recognise a program as the composite counting loop when run with register `a` set to
+    /// `a`. The set up code is run on the interpreter to find the values of `b` and `c`, and the
+    /// remainder of the program is checked instruction by instruction.
+    pub fn recognise(program: &[Instruction], a: i64) -> Result<CompositeLoop, RecognitionError> {
+        let body_len = expected_body(0).len();
+        if program.len() != LOOP_START + body_len {
+            return Err(RecognitionError::WrongLength(program.len()));
+        }
+
+        let step = match program[program.len() - 2] {
+            Instruction::Sub('b', Value::Literal(n)) if n < 0 => -n,
+            found => {
+                return Err(RecognitionError::UnexpectedInstruction {
+                    index: program.len() - 2,
+                    expected: Instruction::Sub('b', Value::Literal(-17)),
+                    found,
+                })
+            }
+        };
+
+        for (offset, (&expected, &found)) in expected_body(step)
+            .iter()
+            .zip(program[LOOP_START..].iter())
+            .enumerate()
+        {
+            if expected != found {
+                return Err(RecognitionError::UnexpectedInstruction {
+                    index: LOOP_START + offset,
+                    expected,
+                    found,
+                });
+            }
+        }
+
+        let mut coprocessor = Coprocessor::new(&program[..LOOP_START + 1]);
+        coprocessor.set_register('a', a);
+        let mut steps = 0;
+        while coprocessor.pc() != Some(LOOP_START) {
+            if steps == MAX_SETUP_STEPS || !coprocessor.step() {
+                return Err(RecognitionError::SetupDidNotReachLoop);
+            }
+            steps += 1;
+        }
+
+        let (b, c) = (coprocessor.register('b'), coprocessor.register('c'));
+        if c < b || (c - b) % step != 0 {
+            return Err(RecognitionError::NeverTerminates { b, c, step });
+        }
+
+        Ok(CompositeLoop {
+            start: b,
+            end: c,
+            step,
+        })
+    }
+
+    /// The final value of register `h`, computed directly rather than by running the loop.
+    pub fn count_composites(&self) -> usize {
+        (0..)
+            .map(|i| self.start + i * self.step)
+            .take_while(|&b| b <= self.end)
+            .filter(|&b| is_composite(b))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::parser::parse_program;
+
+    /// A small version of the puzzle input so that the interpreter can run it to completion.
+    const SMALL_PROGRAM: &str = "set b 15
+set c b
+jnz a 2
+jnz 1 5
+mul b 2
+sub b 2
+set c b
+sub c -34
+set f 1
+set d 2
+set e 2
+set g d
+mul g e
+sub g b
+jnz g 2
+set f 0
+sub e -1
+set g e
+sub g b
+jnz g -8
+sub d -1
+set g d
+sub g b
+jnz g -13
+jnz f 2
+sub h -1
+set g b
+sub g c
+jnz g 2
+jnz 1 3
+sub b -17
+jnz 1 -23";
+
+    #[test]
+    fn recognises_small_program() {
+        let program = parse_program(SMALL_PROGRAM).unwrap().1;
+
+        assert_eq!(
+            Ok(CompositeLoop {
+                start: 28,
+                end: 62,
+                step: 17,
+            }),
+            CompositeLoop::recognise(&program, 1)
+        );
+    }
+
+    #[test]
+    fn matches_interpreter() {
+        let program = parse_program(SMALL_PROGRAM).unwrap().1;
+
+        for &a in [0, 1].iter() {
+            let mut coprocessor = Coprocessor::new(&program);
+            coprocessor.set_register('a', a);
+            coprocessor.run();
+
+            let composite_loop = CompositeLoop::recognise(&program, a).unwrap();
+            assert_eq!(
+                coprocessor.register('h') as usize,
+                composite_loop.count_composites()
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_modified_program() {
+        let modified = SMALL_PROGRAM.replace("sub h -1", "sub h -2");
+        let program = parse_program(&modified).unwrap().1;
+
+        assert_eq!(
+            Err(RecognitionError::UnexpectedInstruction {
+                index: 25,
+                expected: Instruction::Sub('h', Value::Literal(-1)),
+                found: Instruction::Sub('h', Value::Literal(-2)),
+            }),
+            CompositeLoop::recognise(&program, 1)
+        );
+    }
+
+    #[test]
+    fn rejects_non_terminating_range() {
+        let modified = SMALL_PROGRAM.replace("sub c -34", "sub c -35");
+        let program = parse_program(&modified).unwrap().1;
+
+        assert_eq!(
+            Err(RecognitionError::NeverTerminates {
+                b: 28,
+                c: 63,
+                step: 17,
+            }),
+            CompositeLoop::recognise(&program, 1)
+        );
+    }
+}
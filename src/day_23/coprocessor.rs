@@ -0,0 +1,186 @@
+use std::fmt;
+
+/// Registers are named by a single letter from `a` to `h`.
+pub type Register = char;
+
+const NUM_REGISTERS: usize = 8;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Value {
+    Register(Register),
+    Literal(i64),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Instruction {
+    Set(Register, Value),
+    Sub(Register, Value),
+    Mul(Register, Value),
+    Jnz(Value, Value),
+}
+
+impl Instruction {
+    pub fn is_mul(&self) -> bool {
+        matches!(*self, Instruction::Mul(_, _))
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Register(r) => write!(f, "{}", r),
+            Value::Literal(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Instruction::Set(r, v) => write!(f, "set {} {}", r, v),
+            Instruction::Sub(r, v) => write!(f, "sub {} {}", r, v),
+            Instruction::Mul(r, v) => write!(f, "mul {} {}", r, v),
+            Instruction::Jnz(x, y) => write!(f, "jnz {} {}", x, y),
+        }
+    }
+}
+
+/// An interpreter for the coprocessor's `set/sub/mul/jnz` instruction set that records how many
+/// times each instruction of the program has been executed.
+pub struct Coprocessor<'a> {
+    program: &'a [Instruction],
+    registers: [i64; NUM_REGISTERS],
+    pc: isize,
+    profile: Vec<usize>,
+}
+
+impl<'a> Coprocessor<'a> {
+    pub fn new(program: &'a [Instruction]) -> Coprocessor<'a> {
+        Coprocessor {
+            program,
+            registers: [0; NUM_REGISTERS],
+            pc: 0,
+            profile: vec![0; program.len()],
+        }
+    }
+
+    fn index_of(register: Register) -> usize {
+        assert!(('a'..='h').contains(&register), "Unknown register {}", register);
+        (register as u8 - b'a') as usize
+    }
+
+    pub fn register(&self, register: Register) -> i64 {
+        self.registers[Coprocessor::index_of(register)]
+    }
+
+    pub fn set_register(&mut self, register: Register, value: i64) {
+        self.registers[Coprocessor::index_of(register)] = value;
+    }
+
+    fn value(&self, value: Value) -> i64 {
+        match value {
+            Value::Register(r) => self.register(r),
+            Value::Literal(n) => n,
+        }
+    }
+
+    /// The index of the next instruction to be executed, or `None` if the program has halted.
+    pub fn pc(&self) -> Option<usize> {
+        if self.pc >= 0 && (self.pc as usize) < self.program.len() {
+            Some(self.pc as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Execute a single instruction. Returns `false` if the program had already halted.
+    pub fn step(&mut self) -> bool {
+        let pc = match self.pc() {
+            Some(pc) => pc,
+            None => return false,
+        };
+
+        self.profile[pc] += 1;
+
+        match self.program[pc] {
+            Instruction::Set(r, v) => {
+                let value = self.value(v);
+                self.set_register(r, value);
+            }
+            Instruction::Sub(r, v) => {
+                let value = self.register(r) - self.value(v);
+                self.set_register(r, value);
+            }
+            Instruction::Mul(r, v) => {
+                let value = self.register(r) * self.value(v);
+                self.set_register(r, value);
+            }
+            Instruction::Jnz(x, y) => {
+                if self.value(x) != 0 {
+                    self.pc += self.value(y) as isize;
+                    return true;
+                }
+            }
+        }
+
+        self.pc += 1;
+        true
+    }
+
+    /// Run the program until the program counter leaves the program.
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+
+    /// The number of times each instruction has been executed, indexed by instruction.
+    pub fn profile(&self) -> &[usize] {
+        &self.profile
+    }
+
+    /// The total number of times any instruction matching the predicate has been executed.
+    pub fn executions_of<P>(&self, predicate: P) -> usize
+    where
+        P: Fn(&Instruction) -> bool,
+    {
+        self.program
+            .iter()
+            .zip(self.profile.iter())
+            .filter(|&(instr, _)| predicate(instr))
+            .map(|(_, &count)| count)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::Instruction::*;
+    use super::Value::*;
+
+    #[test]
+    fn run_records_profile() {
+        // Multiply `b` by 2 three times, counting `a` down from 3.
+        let program = [
+            Set('a', Literal(3)),
+            Set('b', Literal(1)),
+            Mul('b', Literal(2)),
+            Sub('a', Literal(1)),
+            Jnz(Register('a'), Literal(-2)),
+        ];
+
+        let mut coprocessor = Coprocessor::new(&program);
+        coprocessor.run();
+
+        assert_eq!(0, coprocessor.register('a'));
+        assert_eq!(8, coprocessor.register('b'));
+        assert_eq!(None, coprocessor.pc());
+        assert_eq!(&[1, 1, 3, 3, 3], coprocessor.profile());
+        assert_eq!(3, coprocessor.executions_of(Instruction::is_mul));
+    }
+
+    #[test]
+    fn display_instruction() {
+        assert_eq!("sub b -100000", Sub('b', Literal(-100000)).to_string());
+        assert_eq!("jnz g -8", Jnz(Register('g'), Literal(-8)).to_string());
+    }
+}
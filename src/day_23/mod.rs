@@ -0,0 +1,41 @@
+mod composite_loop;
+mod coprocessor;
+mod parser;
+
+use super::Day;
+
+use self::coprocessor::Instruction;
+
+pub use self::composite_loop::{CompositeLoop, RecognitionError};
+pub use self::coprocessor::Coprocessor;
+
+/// [Day 23](https://adventofcode.com/2017/day/23) - Debug the experimental coprocessor by counting
+/// its multiplications, then work out what the program computes with the debug switch turned off.
+pub struct Day23 {
+    program: Vec<Instruction>,
+}
+
+impl<'a> Day<'a> for Day23 {
+    const NUM: u32 = 23;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn from_str(input: &str) -> Self {
+        let (unused, program) = parser::parse_program(input).unwrap();
+        assert_eq!("", unused);
+
+        Day23 { program }
+    }
+
+    fn part_1(&self) -> Self::Output1 {
+        let mut coprocessor = Coprocessor::new(&self.program);
+        coprocessor.run();
+        coprocessor.executions_of(Instruction::is_mul)
+    }
+
+    fn part_2(&self) -> Self::Output2 {
+        CompositeLoop::recognise(&self.program, 1)
+            .unwrap_or_else(|err| panic!("Unrecognised coprocessor program: {}", err))
+            .count_composites()
+    }
+}
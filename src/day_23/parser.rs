@@ -0,0 +1,46 @@
+use super::coprocessor::{Instruction, Register, Value};
+use super::super::util::parse_i64;
+
+named!(parse_register(&str) -> Register, one_of!("abcdefgh"));
+
+named!(parse_value(&str) -> Value, alt_complete!(
+    map!(parse_i64, Value::Literal) |
+    map!(parse_register, Value::Register)
+));
+
+named!(parse_instruction(&str) -> Instruction, alt_complete!(
+    do_parse!(tag_s!("set ") >> r: parse_register >> tag_s!(" ") >> v: parse_value >> (Instruction::Set(r, v))) |
+    do_parse!(tag_s!("sub ") >> r: parse_register >> tag_s!(" ") >> v: parse_value >> (Instruction::Sub(r, v))) |
+    do_parse!(tag_s!("mul ") >> r: parse_register >> tag_s!(" ") >> v: parse_value >> (Instruction::Mul(r, v))) |
+    do_parse!(tag_s!("jnz ") >> x: parse_value >> tag_s!(" ") >> y: parse_value >> (Instruction::Jnz(x, y)))
+));
+
+named!(pub parse_program(&str) -> Vec<Instruction>, many1!(ws!(parse_instruction)));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_single_instruction() {
+        use super::Instruction::*;
+        use super::Value::*;
+
+        assert_eq!(Set('b', Literal(81)), parse_instruction("set b 81").unwrap().1);
+        assert_eq!(Sub('b', Literal(-100000)), parse_instruction("sub b -100000").unwrap().1);
+        assert_eq!(Mul('g', Register('e')), parse_instruction("mul g e").unwrap().1);
+        assert_eq!(Jnz(Literal(1), Literal(-23)), parse_instruction("jnz 1 -23").unwrap().1);
+    }
+
+    #[test]
+    fn parse_many_instructions() {
+        let input = "set c b
+jnz a 2
+jnz 1 5";
+
+        let (unused, program) = parse_program(input).unwrap();
+
+        assert_eq!("", unused);
+        assert_eq!(3, program.len());
+    }
+}
@@ -0,0 +1,182 @@
+use super::component::Component;
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A chain of components, in the order they are connected starting from port 0.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Bridge {
+    pub components: Vec<Component>,
+}
+
+impl Bridge {
+    pub fn strength(&self) -> usize {
+        self.components.iter().map(Component::strength).sum()
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+impl fmt::Display for Bridge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, component) in self.components.iter().enumerate() {
+            if i > 0 {
+                write!(f, "--")?;
+            }
+            write!(f, "{}", component)?;
+        }
+        Ok(())
+    }
+}
+
+/// The best bridges found by an exhaustive search over all bridges starting at port 0.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BridgeSearch {
+    /// The bridge with the greatest strength.
+    pub strongest: Bridge,
+    /// The strongest of the bridges with the greatest number of components.
+    pub longest: Bridge,
+}
+
+/// The state of the depth first search. Components are referred to by their index in
+/// `components`, with `used` holding one bit per component in 64 bit words.
+struct Searcher<'a> {
+    components: &'a [Component],
+    by_port: Vec<Vec<usize>>,
+    used: Vec<u64>,
+    path: Vec<usize>,
+    strength: usize,
+    strongest: (usize, Vec<usize>),
+    longest: (usize, usize, Vec<usize>),
+}
+
+impl<'a> Searcher<'a> {
+    fn new(components: &'a [Component]) -> Searcher<'a> {
+        let max_port = components
+            .iter()
+            .map(|component| component.a.max(component.b))
+            .max()
+            .unwrap_or(0);
+        let mut by_port = vec![vec![]; max_port + 1];
+
+        for (i, component) in components.iter().enumerate() {
+            by_port[component.a].push(i);
+            if component.b != component.a {
+                by_port[component.b].push(i);
+            }
+        }
+
+        Searcher {
+            components,
+            by_port,
+            used: vec![0; components.len().div_ceil(64)],
+            path: vec![],
+            strength: 0,
+            strongest: (0, vec![]),
+            longest: (0, 0, vec![]),
+        }
+    }
+
+    fn record(&mut self) {
+        if self.strength > self.strongest.0 {
+            self.strongest = (self.strength, self.path.clone());
+        }
+
+        let (length, strength, _) = self.longest;
+        let longer = match self.path.len().cmp(&length) {
+            Ordering::Greater => true,
+            Ordering::Equal => self.strength > strength,
+            Ordering::Less => false,
+        };
+        if longer {
+            self.longest = (self.path.len(), self.strength, self.path.clone());
+        }
+    }
+
+    fn search(&mut self, port: usize) {
+        self.record();
+
+        for i in 0..self.by_port[port].len() {
+            let index = self.by_port[port][i];
+            let (word, bit) = (index / 64, 1 << (index % 64));
+            if self.used[word] & bit != 0 {
+                continue;
+            }
+
+            let component = self.components[index];
+            self.used[word] |= bit;
+            self.path.push(index);
+            self.strength += component.strength();
+
+            self.search(component.other_port(port));
+
+            self.strength -= component.strength();
+            self.path.pop();
+            self.used[word] &= !bit;
+        }
+    }
+
+    fn bridge(&self, path: &[usize]) -> Bridge {
+        Bridge { components: path.iter().map(|&i| self.components[i]).collect() }
+    }
+}
+
+impl BridgeSearch {
+    pub fn new(components: &[Component]) -> BridgeSearch {
+        let mut searcher = Searcher::new(components);
+        searcher.search(0);
+
+        BridgeSearch {
+            strongest: searcher.bridge(&searcher.strongest.1),
+            longest: searcher.bridge(&searcher.longest.2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::component::parse_components;
+    use super::super::tests::INPUT;
+
+    #[test]
+    fn strongest_bridge() {
+        let search = BridgeSearch::new(&parse_components(INPUT).unwrap());
+
+        assert_eq!("0/1--10/1--9/10", search.strongest.to_string());
+        assert_eq!(31, search.strongest.strength());
+    }
+
+    #[test]
+    fn longest_bridge() {
+        let search = BridgeSearch::new(&parse_components(INPUT).unwrap());
+
+        assert_eq!("0/2--2/2--2/3--3/5", search.longest.to_string());
+        assert_eq!(19, search.longest.strength());
+    }
+
+    #[test]
+    fn many_components() {
+        let components = (0..300)
+            .map(|port| Component::new(port, port + 1))
+            .collect::<Vec<_>>();
+        let search = BridgeSearch::new(&components);
+
+        assert_eq!(300, search.longest.len());
+        assert_eq!(300 * 300, search.strongest.strength());
+    }
+
+    #[test]
+    fn no_components() {
+        let search = BridgeSearch::new(&[]);
+
+        assert!(search.strongest.is_empty());
+        assert!(search.longest.is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+use super::super::util::parse_usize;
+
+use nom::IResult;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Component {
+    pub a: usize,
+    pub b: usize,
+}
+
+impl Component {
+    pub fn new(a: usize, b: usize) -> Component {
+        Component { a, b }
+    }
+
+    pub fn strength(&self) -> usize {
+        self.a + self.b
+    }
+
+    /// The port on the other side of this component when connected by `port`.
+    pub fn other_port(&self, port: usize) -> usize {
+        if self.a == port {
+            self.b
+        } else {
+            self.a
+        }
+    }
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.a, self.b)
+    }
+}
+
+/// A line which is not a component of the form `a/b`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseComponentError(pub String);
+
+impl fmt::Display for ParseComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` is not a component of the form a/b", self.0)
+    }
+}
+
+named!(parse_component(&str) -> Component, do_parse!(
+    a: call!(parse_usize) >>
+    tag_s!("/") >>
+    b: call!(parse_usize) >>
+    (Component::new(a, b))
+));
+
+impl FromStr for Component {
+    type Err = ParseComponentError;
+
+    fn from_str(input: &str) -> Result<Component, ParseComponentError> {
+        match parse_component(input.trim()) {
+            IResult::Done("", component) => Ok(component),
+            _ => Err(ParseComponentError(input.trim().to_owned())),
+        }
+    }
+}
+
+/// Parse one component from each line, skipping blank lines.
+pub fn parse_components(input: &str) -> Result<Vec<Component>, ParseComponentError> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_input_components() {
+        let input = "0/2
+2/2
+10/1";
+        let output = vec![
+            Component::new(0, 2),
+            Component::new(2, 2),
+            Component::new(10, 1),
+        ];
+
+        assert_eq!(Ok(output), parse_components(input));
+    }
+
+    #[test]
+    fn invalid_component() {
+        assert_eq!(
+            Err(ParseComponentError("3-4".to_owned())),
+            parse_components("0/2\n3-4")
+        );
+        assert!("1/2/3".parse::<Component>().is_err());
+    }
+
+    #[test]
+    fn other_port() {
+        assert_eq!(9, Component::new(9, 10).other_port(10));
+        assert_eq!(10, Component::new(9, 10).other_port(9));
+        assert_eq!(2, Component::new(2, 2).other_port(2));
+    }
+}
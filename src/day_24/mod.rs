@@ -0,0 +1,59 @@
+mod bridge;
+mod component;
+
+use super::Day;
+
+pub use self::bridge::{Bridge, BridgeSearch};
+pub use self::component::{parse_components, Component, ParseComponentError};
+
+/// [Day 24](https://adventofcode.com/2017/day/24) - Build the strongest bridge, and the strongest
+/// of the longest bridges, out of magnetic components.
+pub struct Day24 {
+    search: BridgeSearch,
+}
+
+impl<'a> Day<'a> for Day24 {
+    const NUM: u32 = 24;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn from_str(input: &str) -> Self {
+        let components =
+            parse_components(input).unwrap_or_else(|err| panic!("Invalid components: {}", err));
+
+        Day24 { search: BridgeSearch::new(&components) }
+    }
+
+    fn part_1(&self) -> Self::Output1 {
+        self.search.strongest.strength()
+    }
+
+    fn part_2(&self) -> Self::Output2 {
+        self.search.longest.strength()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The example components from the puzzle, shared with the tests of the submodules.
+    pub const INPUT: &str = "0/2
+2/2
+2/3
+3/4
+3/5
+0/1
+10/1
+9/10";
+
+    #[test]
+    fn part_1() {
+        assert_eq!(31, Day24::from_str(INPUT).part_1());
+    }
+
+    #[test]
+    fn part_2() {
+        assert_eq!(19, Day24::from_str(INPUT).part_2());
+    }
+}
@@ -0,0 +1,191 @@
+use super::parser::{self, RawBlueprint};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+/// What the machine does when it reads a value in a given state.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Action {
+    pub write: bool,
+    pub direction: Direction,
+    pub next_state: usize,
+}
+
+/// A blueprint whose text does not parse, or whose states do not form a complete transition
+/// table.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BlueprintError {
+    /// The given line could not be parsed, or is empty if the blueprint stopped part way through.
+    Syntax(String),
+    /// A state is defined more than once.
+    DuplicateState(String),
+    /// A state has more than one rule for the same current value.
+    DuplicateTransition { state: String, value: bool },
+    /// A state has no rule for one of the current values.
+    MissingTransition { state: String, value: bool },
+    /// The blueprint refers to a state which is never defined.
+    UnknownState(String),
+}
+
+impl fmt::Display for BlueprintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BlueprintError::Syntax(ref line) => write!(f, "unable to parse blueprint at `{}`", line),
+            BlueprintError::DuplicateState(ref state) => {
+                write!(f, "state {} is defined more than once", state)
+            }
+            BlueprintError::DuplicateTransition { ref state, value } => write!(
+                f,
+                "state {} has more than one rule for the current value {}",
+                state,
+                value as u8
+            ),
+            BlueprintError::MissingTransition { ref state, value } => write!(
+                f,
+                "state {} has no rule for the current value {}",
+                state,
+                value as u8
+            ),
+            BlueprintError::UnknownState(ref state) => write!(f, "state {} is never defined", state),
+        }
+    }
+}
+
+/// A Turing machine blueprint: the transition table indexed by state and then by the current
+/// value, along with where to start and how many steps to run for.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Blueprint {
+    pub state_names: Vec<String>,
+    pub transitions: Vec<[Action; 2]>,
+    pub start_state: usize,
+    pub steps: usize,
+}
+
+impl Blueprint {
+    fn from_raw(raw: RawBlueprint) -> Result<Blueprint, BlueprintError> {
+        let mut indices = HashMap::new();
+        for (i, state) in raw.states.iter().enumerate() {
+            if indices.insert(state.name, i).is_some() {
+                return Err(BlueprintError::DuplicateState(state.name.to_owned()));
+            }
+        }
+
+        let index_of = |name: &str| {
+            indices
+                .get(name)
+                .cloned()
+                .ok_or_else(|| BlueprintError::UnknownState(name.to_owned()))
+        };
+
+        let mut transitions = Vec::with_capacity(raw.states.len());
+        for state in raw.states.iter() {
+            let mut actions = [None, None];
+
+            for rule in state.rules.iter() {
+                let action = Action {
+                    write: rule.write,
+                    direction: rule.direction,
+                    next_state: index_of(rule.next)?,
+                };
+
+                if actions[rule.value as usize].replace(action).is_some() {
+                    return Err(BlueprintError::DuplicateTransition {
+                        state: state.name.to_owned(),
+                        value: rule.value,
+                    });
+                }
+            }
+
+            let missing = |value: bool| BlueprintError::MissingTransition {
+                state: state.name.to_owned(),
+                value,
+            };
+            transitions.push([
+                actions[0].ok_or_else(|| missing(false))?,
+                actions[1].ok_or_else(|| missing(true))?,
+            ]);
+        }
+
+        Ok(Blueprint {
+            state_names: raw.states.iter().map(|state| state.name.to_owned()).collect(),
+            transitions,
+            start_state: index_of(raw.start)?,
+            steps: raw.steps,
+        })
+    }
+}
+
+impl FromStr for Blueprint {
+    type Err = BlueprintError;
+
+    fn from_str(input: &str) -> Result<Blueprint, BlueprintError> {
+        let raw = parser::blueprint(input)
+            .map_err(|line| BlueprintError::Syntax(line.to_owned()))?;
+
+        Blueprint::from_raw(raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::tests::INPUT;
+
+    #[test]
+    fn parse_sample() {
+        let blueprint = INPUT.parse::<Blueprint>().unwrap();
+
+        assert_eq!(vec!["A", "B"], blueprint.state_names);
+        assert_eq!(0, blueprint.start_state);
+        assert_eq!(6, blueprint.steps);
+        assert_eq!(
+            Action {
+                write: false,
+                direction: Direction::Left,
+                next_state: 1,
+            },
+            blueprint.transitions[0][1]
+        );
+    }
+
+    #[test]
+    fn missing_transition() {
+        let end_of_b = INPUT.find("  If the current value is 1:\n    - Write the value 1.").unwrap();
+        let input = &INPUT[..end_of_b];
+
+        assert_eq!(
+            Err(BlueprintError::MissingTransition {
+                state: "B".to_owned(),
+                value: true,
+            }),
+            input.parse::<Blueprint>()
+        );
+    }
+
+    #[test]
+    fn unknown_state() {
+        let input = INPUT.replace("Continue with state A.", "Continue with state C.");
+
+        assert_eq!(
+            Err(BlueprintError::UnknownState("C".to_owned())),
+            input.parse::<Blueprint>()
+        );
+    }
+
+    #[test]
+    fn syntax_error() {
+        let input = INPUT.replace("Move one slot to the right", "Move one slot up");
+
+        assert_eq!(
+            Err(BlueprintError::Syntax("- Move one slot up.".to_owned())),
+            input.parse::<Blueprint>()
+        );
+    }
+}
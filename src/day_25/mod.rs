@@ -0,0 +1,73 @@
+mod blueprint;
+mod parser;
+mod turing;
+
+use super::Day;
+
+pub use self::blueprint::{Blueprint, BlueprintError};
+pub use self::turing::{Tape, TuringMachine};
+
+/// [Day 25](https://adventofcode.com/2017/day/25) - Run the Turing machine described by a blueprint
+/// and calculate its diagnostic checksum.
+pub struct Day25 {
+    blueprint: Blueprint,
+}
+
+impl<'a> Day<'a> for Day25 {
+    const NUM: u32 = 25;
+    type Output1 = usize;
+    type Output2 = &'static str;
+
+    fn from_str(input: &str) -> Self {
+        let blueprint = input
+            .parse::<Blueprint>()
+            .unwrap_or_else(|err| panic!("Invalid blueprint: {}", err));
+
+        Day25 { blueprint }
+    }
+
+    fn part_1(&self) -> Self::Output1 {
+        let mut machine = TuringMachine::new(&self.blueprint);
+        machine.run();
+        machine.tape().checksum()
+    }
+
+    fn part_2(&self) -> Self::Output2 {
+        "There is no part 2 on Christmas Day"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The example blueprint from the puzzle, shared with the tests of the submodules.
+    pub const INPUT: &str = "Begin in state A.
+Perform a diagnostic checksum after 6 steps.
+
+In state A:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state B.
+  If the current value is 1:
+    - Write the value 0.
+    - Move one slot to the left.
+    - Continue with state B.
+
+In state B:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the left.
+    - Continue with state A.
+  If the current value is 1:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state A.
+";
+
+    #[test]
+    fn part_1() {
+        assert_eq!(3, Day25::from_str(INPUT).part_1());
+    }
+}
@@ -0,0 +1,189 @@
+use super::super::util::parse_usize;
+use super::blueprint::Direction;
+
+use nom::IResult;
+use std::iter::Peekable;
+
+/// A single `If the current value is ...` block, before state names have been resolved.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RawRule<'a> {
+    pub value: bool,
+    pub write: bool,
+    pub direction: Direction,
+    pub next: &'a str,
+}
+
+/// An `In state ...` block, before it has been checked for missing transitions.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RawState<'a> {
+    pub name: &'a str,
+    pub rules: Vec<RawRule<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RawBlueprint<'a> {
+    pub start: &'a str,
+    pub steps: usize,
+    pub states: Vec<RawState<'a>>,
+}
+
+named!(state_name(&str) -> &str, take_while1_s!(char::is_alphanumeric));
+
+named!(bit(&str) -> bool, map!(one_of!("01"), |c| c == '1'));
+
+named!(direction(&str) -> Direction, alt_complete!(
+    value!(Direction::Left, tag_s!("left")) |
+    value!(Direction::Right, tag_s!("right"))
+));
+
+named!(begin_line(&str) -> &str, ws!(do_parse!(
+    tag_s!("Begin in state") >> start: state_name >> tag_s!(".") >> (start)
+)));
+
+named!(checksum_line(&str) -> usize, ws!(do_parse!(
+    tag_s!("Perform a diagnostic checksum after") >> steps: parse_usize >> tag_s!("steps.") >>
+    (steps)
+)));
+
+named!(state_line(&str) -> &str, ws!(do_parse!(
+    tag_s!("In state") >> name: state_name >> tag_s!(":") >> (name)
+)));
+
+named!(value_line(&str) -> bool, ws!(do_parse!(
+    tag_s!("If the current value is") >> value: bit >> tag_s!(":") >> (value)
+)));
+
+named!(write_line(&str) -> bool, ws!(do_parse!(
+    tag_s!("- Write the value") >> write: bit >> tag_s!(".") >> (write)
+)));
+
+named!(move_line(&str) -> Direction, ws!(do_parse!(
+    tag_s!("- Move one slot to the") >> direction: direction >> tag_s!(".") >> (direction)
+)));
+
+named!(continue_line(&str) -> &str, ws!(do_parse!(
+    tag_s!("- Continue with state") >> next: state_name >> tag_s!(".") >> (next)
+)));
+
+/// The non-blank lines of `input`, trimmed.
+fn lines(input: &str) -> Peekable<impl Iterator<Item = &str>> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .peekable()
+}
+
+/// Parse the whole of `line`, or return it as the error. A missing line is reported as empty.
+fn expect<'a, T>(
+    line: Option<&'a str>,
+    parser: fn(&'a str) -> IResult<&'a str, T>,
+) -> Result<T, &'a str> {
+    let line = line.unwrap_or("");
+
+    match parser(line) {
+        IResult::Done("", value) => Ok(value),
+        _ => Err(line),
+    }
+}
+
+/// A single `If the current value is ...` block of four lines.
+fn rule<'a, I>(lines: &mut I) -> Result<RawRule<'a>, &'a str>
+where
+    I: Iterator<Item = &'a str>,
+{
+    Ok(RawRule {
+        value: expect(lines.next(), value_line)?,
+        write: expect(lines.next(), write_line)?,
+        direction: expect(lines.next(), move_line)?,
+        next: expect(lines.next(), continue_line)?,
+    })
+}
+
+/// An `In state ...` line followed by every rule that comes after it.
+fn state<'a, I>(lines: &mut Peekable<I>) -> Result<RawState<'a>, &'a str>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let name = expect(lines.next(), state_line)?;
+    let mut rules = vec![];
+
+    while lines.peek().is_some_and(|&line| value_line(line).is_done()) {
+        rules.push(rule(lines)?);
+    }
+
+    Ok(RawState { name, rules })
+}
+
+/// Parse a blueprint a line at a time, returning the first line that does not fit as the error.
+pub fn blueprint(input: &str) -> Result<RawBlueprint<'_>, &str> {
+    let mut lines = lines(input);
+    let start = expect(lines.next(), begin_line)?;
+    let steps = expect(lines.next(), checksum_line)?;
+
+    let mut states = vec![state(&mut lines)?];
+    while lines.peek().is_some() {
+        states.push(state(&mut lines)?);
+    }
+
+    Ok(RawBlueprint {
+        start,
+        steps,
+        states,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rule() {
+        let input = "If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state B.";
+        let output = RawRule {
+            value: false,
+            write: true,
+            direction: Direction::Right,
+            next: "B",
+        };
+
+        assert_eq!(Ok(output), rule(&mut lines(input)));
+    }
+
+    #[test]
+    fn parse_state() {
+        let input = "In state B:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the left.
+    - Continue with state A.
+  If the current value is 1:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state A.
+";
+        let mut lines = lines(input);
+        let output = state(&mut lines).unwrap();
+
+        assert_eq!(None, lines.next());
+        assert_eq!("B", output.name);
+        assert_eq!(2, output.rules.len());
+        assert_eq!(Direction::Left, output.rules[0].direction);
+        assert!(output.rules[1].value);
+    }
+
+    #[test]
+    fn reports_failing_line() {
+        let input = "In state A:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot up.
+    - Continue with state B.";
+
+        assert_eq!(Err("- Move one slot up."), state(&mut lines(input)));
+        assert_eq!(Err(""), rule(&mut lines("If the current value is 0:")));
+    }
+}
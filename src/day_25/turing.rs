@@ -0,0 +1,131 @@
+use super::blueprint::{Blueprint, Direction};
+
+use std::collections::VecDeque;
+
+/// An infinite tape of zeroes, which grows in either direction as the cursor moves off its end.
+#[derive(Debug)]
+pub struct Tape {
+    cells: VecDeque<bool>,
+    cursor: usize,
+}
+
+impl Default for Tape {
+    fn default() -> Tape {
+        Tape::new()
+    }
+}
+
+impl Tape {
+    pub fn new() -> Tape {
+        let mut cells = VecDeque::new();
+        cells.push_back(false);
+        Tape { cells, cursor: 0 }
+    }
+
+    pub fn read(&self) -> bool {
+        self.cells[self.cursor]
+    }
+
+    pub fn write(&mut self, value: bool) {
+        self.cells[self.cursor] = value;
+    }
+
+    pub fn move_cursor(&mut self, direction: Direction) {
+        match direction {
+            Direction::Left => {
+                if self.cursor == 0 {
+                    self.cells.push_front(false);
+                } else {
+                    self.cursor -= 1;
+                }
+            }
+            Direction::Right => {
+                self.cursor += 1;
+                if self.cursor == self.cells.len() {
+                    self.cells.push_back(false);
+                }
+            }
+        }
+    }
+
+    /// The number of ones on the tape.
+    pub fn checksum(&self) -> usize {
+        self.cells.iter().filter(|&&cell| cell).count()
+    }
+}
+
+pub struct TuringMachine<'a> {
+    blueprint: &'a Blueprint,
+    state: usize,
+    tape: Tape,
+}
+
+impl<'a> TuringMachine<'a> {
+    pub fn new(blueprint: &'a Blueprint) -> TuringMachine<'a> {
+        TuringMachine {
+            blueprint,
+            state: blueprint.start_state,
+            tape: Tape::new(),
+        }
+    }
+
+    pub fn step(&mut self) {
+        let action = self.blueprint.transitions[self.state][self.tape.read() as usize];
+
+        self.tape.write(action.write);
+        self.tape.move_cursor(action.direction);
+        self.state = action.next_state;
+    }
+
+    /// Run the machine for the number of steps given in the blueprint.
+    pub fn run(&mut self) {
+        for _ in 0..self.blueprint.steps {
+            self.step();
+        }
+    }
+
+    pub fn state_name(&self) -> &str {
+        &self.blueprint.state_names[self.state]
+    }
+
+    pub fn tape(&self) -> &Tape {
+        &self.tape
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tape_grows_both_ways() {
+        let mut tape = Tape::new();
+
+        tape.write(true);
+        tape.move_cursor(Direction::Left);
+        tape.move_cursor(Direction::Left);
+        tape.write(true);
+        assert!(tape.read());
+
+        for _ in 0..4 {
+            tape.move_cursor(Direction::Right);
+        }
+        assert!(!tape.read());
+        tape.write(true);
+
+        tape.move_cursor(Direction::Left);
+        tape.move_cursor(Direction::Left);
+        assert!(tape.read());
+        assert_eq!(3, tape.checksum());
+    }
+
+    #[test]
+    fn default_tape() {
+        let mut tape = Tape::default();
+
+        assert!(!tape.read());
+        tape.write(true);
+        assert!(tape.read());
+        assert_eq!(1, tape.checksum());
+    }
+}
@@ -8,6 +8,7 @@
 #[macro_use]
 extern crate nom;
 extern crate petgraph;
+extern crate unicode_normalization;
 
 mod day;
 
@@ -35,5 +36,8 @@ use_days!(
     day_13,
     day_14,
     day_15,
-    day_16
+    day_16,
+    day_23,
+    day_24,
+    day_25
 );
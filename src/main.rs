@@ -1,9 +1,14 @@
 extern crate advent_of_code_2017;
 extern crate clap;
 
-use clap::{Arg, App};
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand};
 
 use advent_of_code_2017::*;
+use advent_of_code_2017::util::knot_hash::{KnotHash, KnotHashParams};
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::process;
 
 macro_rules! day {
     ($day:ident => $path:expr) => ({
@@ -12,22 +17,8 @@ macro_rules! day {
     })
 }
 
-pub fn main() {
-    let matches = App::new("Advent of Code 2017")
-        .version("0.1.0")
-        .author("William Woodhead <william.woodhead@lux01.co.uk>")
-        .about(
-            "Calculates the solutions to an implemented day of the Advent of Code 2017",
-        )
-        .arg(
-            Arg::with_name("DAY")
-                .help("The number of the day to run")
-                .required(true)
-                .index(1),
-        )
-        .get_matches();
-
-    match matches.value_of("DAY").unwrap() {
+fn run_day(day: &str) {
+    match day {
         "1" | "01" => day!(Day01 => "input/day_01.txt"),
         "2" | "02" => day!(Day02 => "input/day_02.txt"),
         "3" | "03" => day!(Day03 => "input/day_03.txt"),
@@ -44,6 +35,209 @@ pub fn main() {
         "14" => day!(Day14 => "input/day_14.txt"),
         "15" => day!(Day15 => "input/day_15.txt"),
         "16" => day!(Day16 => "input/day_16.txt"),
+        "23" => day!(Day23 => "input/day_23.txt"),
+        "24" => day!(Day24 => "input/day_24.txt"),
+        "25" => day!(Day25 => "input/day_25.txt"),
         _ => println!("Unknown day"),
     }
-}
\ No newline at end of file
+}
+
+/// Print an error and exit with status 2, leaving status 1 for a failed `--check`.
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(2)
+}
+
+/// Read the input to hash from the command line, a file, or stdin for `-`. Surrounding
+/// whitespace is trimmed from a string argument, as `KnotHash::hash_str` does, but files and
+/// stdin are hashed exactly as read.
+fn knot_hash_input(matches: &ArgMatches) -> Vec<u8> {
+    let mut buffer = vec![];
+
+    let result = match (matches.value_of("STRING"), matches.value_of("file")) {
+        (Some("-"), _) => io::stdin().read_to_end(&mut buffer),
+        (Some(string), _) => return string.trim().as_bytes().to_vec(),
+        (None, Some(path)) => File::open(path).and_then(|mut file| file.read_to_end(&mut buffer)),
+        (None, None) => unreachable!("clap requires one of STRING or --file"),
+    };
+
+    if let Err(err) = result {
+        fail(&format!("failed to read input: {}", err));
+    }
+    buffer
+}
+
+fn knot_hash(matches: &ArgMatches) {
+    let input = knot_hash_input(matches);
+
+    let hash = if matches.is_present("lengths") {
+        let input = std::str::from_utf8(&input)
+            .unwrap_or_else(|_| fail("a list of lengths must be valid UTF-8"));
+        KnotHash::hash_list_of_lengths(input).unwrap_or_else(|err| fail(&err.to_string()))
+    } else if matches.is_present("raw") {
+        let params = KnotHashParams::default()
+            .with_suffix(vec![])
+            .expect("Any list can be hashed without a suffix");
+        params.hash_bytes(&input)
+    } else {
+        KnotHashParams::default().hash_bytes(&input)
+    };
+
+    println!("{}", hash);
+
+    if let Some(expected) = matches.value_of("check") {
+        let expected = expected
+            .parse::<KnotHash>()
+            .unwrap_or_else(|err| fail(&format!("invalid --check digest: {}", err)));
+
+        if hash != expected {
+            eprintln!("Digest does not match {}", expected);
+            process::exit(1);
+        }
+    }
+}
+
+/// Parse a numeric option, exiting with an error if it is not a number.
+fn number_arg(matches: &ArgMatches, name: &str) -> Option<u64> {
+    matches.value_of(name).map(|value| {
+        value
+            .parse()
+            .unwrap_or_else(|_| fail(&format!("--{} must be a number, not {:?}", name, value)))
+    })
+}
+
+fn spiral(matches: &ArgMatches) {
+    let radius = number_arg(matches, "radius").unwrap_or(2);
+    let target = number_arg(matches, "target");
+
+    let rendered = if matches.is_present("summed") {
+        render_spiral(CachedSummedSpiralIterator::new(), radius, target)
+    } else {
+        render_spiral(SpiralIterator::new(), radius, target)
+    };
+
+    println!("{}", rendered);
+}
+
+/// Print the Graphviz DOT for a day's puzzle input.
+fn dot(matches: &ArgMatches) {
+    let dot = match matches.value_of("DAY").unwrap() {
+        "7" | "07" => {
+            let input = file_as_string("input/day_07.txt");
+            let tower = ProgramTree::parse(&input)
+                .unwrap_or_else(|err| fail(&format!("invalid tower: {}", err)));
+            tower.to_dot()
+        }
+        "12" => {
+            let input = file_as_string("input/day_12.txt");
+            let mut village = ProgramVillage::new(input.lines().count());
+            village.populate_village(&input);
+            village.to_dot()
+        }
+        day => fail(&format!("day {} has no graph to draw, try 7 or 12", day)),
+    };
+
+    print!("{}", dot);
+}
+
+pub fn main() {
+    let matches = App::new("Advent of Code 2017")
+        .version("0.1.0")
+        .author("William Woodhead <william.woodhead@lux01.co.uk>")
+        .about(
+            "Calculates the solutions to an implemented day of the Advent of Code 2017",
+        )
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .arg(
+            Arg::with_name("DAY")
+                .help("The number of the day to run")
+                .required(true)
+                .index(1),
+        )
+        .subcommand(
+            SubCommand::with_name("knot-hash")
+                .about(
+                    "Prints the knot hash of a string, a file, or stdin. Surrounding whitespace \
+                     is ignored for a string, but files and stdin are hashed byte for byte.",
+                )
+                .arg(
+                    Arg::with_name("STRING")
+                        .help("The string to hash, or - to read from stdin")
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .long("file")
+                        .short("f")
+                        .takes_value(true)
+                        .value_name("PATH")
+                        .help("Hash the contents of a file"),
+                )
+                .group(
+                    ArgGroup::with_name("input")
+                        .args(&["STRING", "file"])
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("lengths")
+                        .long("lengths")
+                        .help("Treat the input as a comma separated list of lengths"),
+                )
+                .arg(
+                    Arg::with_name("raw")
+                        .long("raw")
+                        .conflicts_with("lengths")
+                        .help("Do not append the standard suffix to the input"),
+                )
+                .arg(
+                    Arg::with_name("check")
+                        .long("check")
+                        .takes_value(true)
+                        .value_name("HEX")
+                        .help("Exit with status 1 unless the hash matches this digest"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("spiral")
+                .about("Draws the Day 3 number spiral, highlighting a target and its path home")
+                .arg(
+                    Arg::with_name("radius")
+                        .long("radius")
+                        .short("r")
+                        .takes_value(true)
+                        .value_name("RINGS")
+                        .help("The number of rings around the origin to draw [default: 2]"),
+                )
+                .arg(
+                    Arg::with_name("target")
+                        .long("target")
+                        .short("t")
+                        .takes_value(true)
+                        .value_name("NUMBER")
+                        .help("Highlight the cell holding this number"),
+                )
+                .arg(
+                    Arg::with_name("summed")
+                        .long("summed")
+                        .help("Draw the spiral of neighbour sums from part 2"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("dot")
+                .about("Prints the Day 7 tower or Day 12 village as a Graphviz DOT graph")
+                .arg(
+                    Arg::with_name("DAY")
+                        .help("The day to draw, either 7 or 12")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .get_matches();
+
+    match matches.subcommand() {
+        ("knot-hash", Some(knot_hash_matches)) => knot_hash(knot_hash_matches),
+        ("spiral", Some(spiral_matches)) => spiral(spiral_matches),
+        ("dot", Some(dot_matches)) => dot(dot_matches),
+        _ => run_day(matches.value_of("DAY").unwrap()),
+    }
+}
@@ -0,0 +1,189 @@
+//! Cycle detection for sequences `x0, f(x0), f(f(x0)), ..` generated by repeatedly applying a step
+//! function to a state.
+//!
+//! Every such sequence over a finite set of states eventually repeats, so it consists of a tail of
+//! `mu` states that are never seen again followed by a loop of `lambda` states. Both detectors
+//! will loop forever if the sequence never repeats.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The shape of a sequence: `mu` states before the loop starts, and `lambda` states in the loop.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Cycle {
+    pub mu: usize,
+    pub lambda: usize,
+}
+
+impl Cycle {
+    /// The smallest number of steps from the initial state that reaches the same state as taking
+    /// `n` steps.
+    pub fn equivalent_steps(&self, n: usize) -> usize {
+        if n < self.mu {
+            n
+        } else {
+            self.mu + (n - self.mu) % self.lambda
+        }
+    }
+}
+
+/// Apply `step` to `initial` `n` times.
+pub fn iterate<S, F>(initial: &S, step: F, n: usize) -> S
+where
+    S: Clone,
+    F: Fn(&S) -> S,
+{
+    (0..n).fold(initial.clone(), |state, _| step(&state))
+}
+
+/// Find the cycle by remembering the index at which every state was first seen. This takes a
+/// single pass over `mu + lambda` states, but stores all of them.
+pub fn find_cycle_hashing<S, F>(initial: &S, step: F) -> Cycle
+where
+    S: Clone + Eq + Hash,
+    F: Fn(&S) -> S,
+{
+    hashing_pass(initial, step).0
+}
+
+/// Find the cycle in the same way as `find_cycle_hashing`, and also return the `mu + lambda`
+/// distinct states in the order they were seen.
+pub fn find_cycle_hashing_with_states<S, F>(initial: &S, step: F) -> (Cycle, Vec<S>)
+where
+    S: Clone + Eq + Hash,
+    F: Fn(&S) -> S,
+{
+    let (cycle, seen) = hashing_pass(initial, step);
+    let mut states = seen.into_iter().collect::<Vec<_>>();
+    states.sort_unstable_by_key(|&(_, index)| index);
+
+    (cycle, states.into_iter().map(|(state, _)| state).collect())
+}
+
+/// The cycle, along with every distinct state mapped to the index at which it was first seen.
+fn hashing_pass<S, F>(initial: &S, step: F) -> (Cycle, HashMap<S, usize>)
+where
+    S: Clone + Eq + Hash,
+    F: Fn(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut state = initial.clone();
+    let mut index = 0;
+
+    loop {
+        let next = step(&state);
+        if let Some(&first_seen) = seen.get(&state) {
+            let cycle = Cycle {
+                mu: first_seen,
+                lambda: index - first_seen,
+            };
+            return (cycle, seen);
+        }
+        seen.insert(state, index);
+        state = next;
+        index += 1;
+    }
+}
+
+/// Find the cycle using Brent's algorithm, which only ever holds two states in memory at the cost
+/// of calling `step` more often than the hashing detector.
+pub fn find_cycle_brent<S, F>(initial: &S, step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: Fn(&S) -> S,
+{
+    // Search successive powers of two for the loop length.
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+
+    while tortoise != hare {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = step(&hare);
+        lambda += 1;
+    }
+
+    // With the hare `lambda` steps ahead, the two meet at the start of the loop.
+    let mut mu = 0;
+    let mut tortoise = initial.clone();
+    let mut hare = iterate(initial, &step, lambda);
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+
+    Cycle { mu, lambda }
+}
+
+/// The state after applying `step` to `initial` `n` times, skipping whole trips around the loop.
+pub fn state_after<S, F>(initial: &S, step: F, n: usize) -> S
+where
+    S: Clone + Eq,
+    F: Fn(&S) -> S,
+{
+    let cycle = find_cycle_brent(initial, &step);
+    iterate(initial, step, cycle.equivalent_steps(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, 2, 3, 4, 5, 2, 3, 4, 5, 2, ..
+    fn step(&x: &usize) -> usize {
+        if x < 5 {
+            x + 1
+        } else {
+            2
+        }
+    }
+
+    #[test]
+    fn detectors_agree() {
+        let cycle = Cycle { mu: 2, lambda: 4 };
+
+        assert_eq!(cycle, find_cycle_hashing(&0, step));
+        assert_eq!(cycle, find_cycle_brent(&0, step));
+    }
+
+    #[test]
+    fn hashing_with_states() {
+        let (cycle, states) = find_cycle_hashing_with_states(&0, step);
+
+        assert_eq!(Cycle { mu: 2, lambda: 4 }, cycle);
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], states);
+    }
+
+    #[test]
+    fn pure_loop() {
+        let cycle = Cycle { mu: 0, lambda: 7 };
+        let rotate = |&x: &u32| (x + 3) % 7;
+
+        assert_eq!(cycle, find_cycle_hashing(&0, rotate));
+        assert_eq!(cycle, find_cycle_brent(&0, rotate));
+    }
+
+    #[test]
+    fn fixed_point() {
+        let cycle = Cycle { mu: 3, lambda: 1 };
+        let saturate = |&x: &u8| x.saturating_add(1).min(3);
+
+        assert_eq!(cycle, find_cycle_hashing(&0, saturate));
+        assert_eq!(cycle, find_cycle_brent(&0, saturate));
+    }
+
+    #[test]
+    fn state_after_many_steps() {
+        assert_eq!(0, state_after(&0, step, 0));
+        assert_eq!(5, state_after(&0, step, 5));
+        assert_eq!(2, state_after(&0, step, 10));
+        assert_eq!(3, state_after(&0, step, 1_000_000_003));
+    }
+}
@@ -0,0 +1,435 @@
+//! Two dimensional grids of cells.
+//!
+//! [`Grid`](struct.Grid.html) is a dense, bounded grid stored in row-major order and indexed by
+//! `(x, y)` with `(0, 0)` in the top left. [`SparseGrid`](struct.SparseGrid.html) is unbounded in
+//! every direction and only stores the cells that have been set.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// The offsets of the four orthogonal neighbours of a cell.
+pub const OFFSETS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// The offsets of all eight neighbours of a cell, including diagonals.
+pub const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// The first row or cell that stopped `Grid::from_ascii`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseGridError {
+    /// A row has a different number of cells to the first row.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A character was not recognised as a cell.
+    InvalidCell { x: usize, y: usize, c: char },
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseGridError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} cells, expected {}",
+                row,
+                found,
+                expected
+            ),
+            ParseGridError::InvalidCell { x, y, c } => {
+                write!(f, "invalid cell {:?} at ({}, {})", c, x, y)
+            }
+        }
+    }
+}
+
+/// A dense grid of `width * height` cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Create a grid by calling `f` with the position of each cell, in row-major order.
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> Grid<T>
+    where
+        F: FnMut((usize, usize)) -> T,
+    {
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(f((x, y)));
+            }
+        }
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parse a grid with one row per line, converting each character with `f`. Every row must
+    /// have the same number of cells, and `f` returns `None` for characters that are not cells.
+    pub fn from_ascii<F>(input: &str, f: F) -> Result<Grid<T>, ParseGridError>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for (y, line) in input.lines().enumerate() {
+            let row_start = cells.len();
+            for (x, c) in line.chars().enumerate() {
+                cells.push(f(c).ok_or(ParseGridError::InvalidCell { x, y, c })?);
+            }
+
+            let row_width = cells.len() - row_start;
+            match width {
+                None => width = Some(row_width),
+                Some(expected) if expected != row_width => {
+                    return Err(ParseGridError::RaggedRow {
+                        row: y,
+                        expected,
+                        found: row_width,
+                    })
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    /// Render the grid with one row per line, converting each cell with `f`.
+    pub fn to_ascii<F>(&self, f: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let mut output = String::with_capacity((self.width + 1) * self.height);
+        for row in self.cells.chunks(self.width.max(1)).take(self.height) {
+            output.extend(row.iter().map(&f));
+            output.push('\n');
+        }
+        output
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        if self.contains(pos) {
+            Some(&self.cells[pos.1 * self.width + pos.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.1 * self.width + pos.0])
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over the positions of every cell in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |i| (i % width, i / width))
+    }
+
+    /// Iterate over every cell and its position in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The positions of the orthogonal neighbours of `pos` that lie inside the grid.
+    pub fn neighbours_4(&self, pos: (usize, usize)) -> Neighbours {
+        Neighbours::new(pos, (self.width, self.height), &OFFSETS_4)
+    }
+
+    /// The positions of the orthogonal and diagonal neighbours of `pos` that lie inside the grid.
+    pub fn neighbours_8(&self, pos: (usize, usize)) -> Neighbours {
+        Neighbours::new(pos, (self.width, self.height), &OFFSETS_8)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &T {
+        self.get(pos).unwrap_or_else(|| {
+            panic!(
+                "Position {:?} is outside a {}x{} grid",
+                pos,
+                self.width,
+                self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos).unwrap_or_else(|| {
+            panic!("Position {:?} is outside a {}x{} grid", pos, width, height)
+        })
+    }
+}
+
+/// An iterator over the neighbours of a cell in a bounded grid.
+pub struct Neighbours {
+    centre: (usize, usize),
+    size: (usize, usize),
+    offsets: &'static [(isize, isize)],
+}
+
+impl Neighbours {
+    fn new(
+        centre: (usize, usize),
+        size: (usize, usize),
+        offsets: &'static [(isize, isize)],
+    ) -> Neighbours {
+        Neighbours {
+            centre,
+            size,
+            offsets,
+        }
+    }
+}
+
+impl Iterator for Neighbours {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((&(dx, dy), rest)) = self.offsets.split_first() {
+            self.offsets = rest;
+
+            let x = self.centre.0 as isize + dx;
+            let y = self.centre.1 as isize + dy;
+            if x >= 0 && y >= 0 && (x as usize) < self.size.0 && (y as usize) < self.size.1 {
+                return Some((x as usize, y as usize));
+            }
+        }
+        None
+    }
+}
+
+/// An unbounded grid which only stores the cells that have been set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<(i64, i64), T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> SparseGrid<T> {
+        SparseGrid { cells: HashMap::new() }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    pub fn get(&self, pos: (i64, i64)) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: (i64, i64)) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    /// Set a cell, returning its previous value if it had one.
+    pub fn insert(&mut self, pos: (i64, i64), value: T) -> Option<T> {
+        self.cells.insert(pos, value)
+    }
+
+    /// The number of cells that have been set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Iterate over the cells that have been set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.cells.iter().map(|(&pos, value)| (pos, value))
+    }
+
+    /// The smallest and largest corners of the rectangle containing every cell that has been set.
+    pub fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        self.cells.keys().fold(None, |bounds, &(x, y)| match bounds {
+            None => Some(((x, y), (x, y))),
+            Some(((min_x, min_y), (max_x, max_y))) => {
+                Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y))))
+            }
+        })
+    }
+
+    /// The positions of the orthogonal neighbours of `pos`.
+    pub fn neighbours_4(&self, pos: (i64, i64)) -> impl Iterator<Item = (i64, i64)> {
+        OFFSETS_4
+            .iter()
+            .map(move |&(dx, dy)| (pos.0 + dx as i64, pos.1 + dy as i64))
+    }
+
+    /// The positions of the orthogonal and diagonal neighbours of `pos`.
+    pub fn neighbours_8(&self, pos: (i64, i64)) -> impl Iterator<Item = (i64, i64)> {
+        OFFSETS_8
+            .iter()
+            .map(move |&(dx, dy)| (pos.0 + dx as i64, pos.1 + dy as i64))
+    }
+
+    /// Render the rectangle given by `bounds()` with one row per line, from the smallest `y` to
+    /// the largest. Cells that have not been set are drawn as `empty`.
+    pub fn to_ascii<F>(&self, empty: char, f: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let mut output = String::new();
+        if let Some(((min_x, min_y), (max_x, max_y))) = self.bounds() {
+            for y in min_y..max_y + 1 {
+                for x in min_x..max_x + 1 {
+                    output.push(self.get((x, y)).map(&f).unwrap_or(empty));
+                }
+                output.push('\n');
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#..
+.#.
+##.";
+
+    fn parse(input: &str) -> Result<Grid<bool>, ParseGridError> {
+        Grid::from_ascii(input, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn ascii_round_trip() {
+        let grid = parse(INPUT).unwrap();
+
+        assert_eq!(3, grid.width());
+        assert_eq!(3, grid.height());
+        assert_eq!(Some(&true), grid.get((1, 1)));
+        assert_eq!(Some(&false), grid.get((2, 2)));
+        assert_eq!(None, grid.get((3, 0)));
+        assert_eq!(
+            format!("{}\n", INPUT),
+            grid.to_ascii(|&cell| if cell { '#' } else { '.' })
+        );
+    }
+
+    #[test]
+    fn ascii_errors() {
+        assert_eq!(
+            Err(ParseGridError::RaggedRow {
+                row: 1,
+                expected: 3,
+                found: 2,
+            }),
+            parse("#..\n.#\n")
+        );
+        assert_eq!(
+            Err(ParseGridError::InvalidCell { x: 1, y: 0, c: 'x' }),
+            parse("#x.")
+        );
+    }
+
+    #[test]
+    fn bounded_neighbours() {
+        let grid = Grid::new(3, 2, 0);
+
+        assert_eq!(
+            vec![(1, 0), (0, 1)],
+            grid.neighbours_4((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(1, 0), (0, 1), (2, 1)],
+            grid.neighbours_4((1, 1)).collect::<Vec<_>>()
+        );
+        assert_eq!(5, grid.neighbours_8((1, 0)).count());
+        assert_eq!(3, grid.neighbours_8((2, 1)).count());
+    }
+
+    #[test]
+    fn from_fn_is_row_major() {
+        let grid = Grid::from_fn(3, 2, |(x, y)| x + 10 * y);
+
+        assert_eq!(12, grid[(2, 1)]);
+        assert_eq!(
+            vec![0, 1, 2, 10, 11, 12],
+            grid.iter().map(|(_, &v)| v).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn sparse_grid() {
+        let mut grid = SparseGrid::new();
+        grid.insert((-1, 2), 'a');
+        grid.insert((1, 0), 'b');
+
+        assert_eq!(Some(((-1, 0), (1, 2))), grid.bounds());
+        assert_eq!("..b\n...\na..\n", grid.to_ascii('.', |&c| c));
+        assert_eq!(8, grid.neighbours_8((0, 0)).count());
+        assert!(grid.neighbours_4((0, 0)).any(|pos| pos == (1, 0)));
+    }
+}
@@ -1,32 +1,270 @@
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
 use std::ops::Index;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The lengths appended to the input of every knot hash.
+const STANDARD_SUFFIX: [usize; 5] = [17, 31, 73, 47, 23];
+
+/// The largest list a knot hash can be calculated over, as every element must fit in a byte.
+const MAX_LIST_SIZE: usize = 256;
+
+const STANDARD_ROUNDS: usize = 64;
+
+const STANDARD_BLOCK_SIZE: usize = 16;
+
+/// The configuration of a knot hash. The default is the standard knot hash: a 256 element list,
+/// 64 rounds, 16 element blocks, and the suffix `17, 31, 73, 47, 23`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnotHashParams {
+    list_size: usize,
+    rounds: usize,
+    block_size: usize,
+    suffix: Vec<usize>,
+}
+
+impl Default for KnotHashParams {
+    fn default() -> KnotHashParams {
+        KnotHashParams {
+            list_size: MAX_LIST_SIZE,
+            rounds: STANDARD_ROUNDS,
+            block_size: STANDARD_BLOCK_SIZE,
+            suffix: STANDARD_SUFFIX.to_vec(),
+        }
+    }
+}
+
+/// A `KnotHashParams` that cannot be hashed with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KnotHashParamsError {
+    /// The list must have between 1 and 256 elements.
+    ListSize(usize),
+    /// The block size must be non-zero and divide the list size.
+    BlockSize { block_size: usize, list_size: usize },
+    /// A length in the suffix is longer than the list.
+    SuffixLength { length: usize, list_size: usize },
+}
+
+impl fmt::Display for KnotHashParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KnotHashParamsError::ListSize(list_size) => write!(
+                f,
+                "a knot hash list must have between 1 and {} elements, not {}",
+                MAX_LIST_SIZE,
+                list_size
+            ),
+            KnotHashParamsError::BlockSize {
+                block_size,
+                list_size,
+            } => write!(
+                f,
+                "a block size of {} does not divide a list of {} elements",
+                block_size,
+                list_size
+            ),
+            KnotHashParamsError::SuffixLength { length, list_size } => write!(
+                f,
+                "the suffix length {} is longer than a list of {} elements",
+                length,
+                list_size
+            ),
+        }
+    }
+}
+
+/// A list of lengths that cannot be hashed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KnotHashLengthsError {
+    /// An entry in a comma separated list is not a number.
+    Invalid(String),
+    /// A length is longer than the list.
+    TooLong { length: usize, list_size: usize },
+}
+
+impl fmt::Display for KnotHashLengthsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KnotHashLengthsError::Invalid(ref length) => {
+                write!(f, "{:?} is not a length", length)
+            }
+            KnotHashLengthsError::TooLong { length, list_size } => write!(
+                f,
+                "the length {} is longer than a list of {} elements",
+                length,
+                list_size
+            ),
+        }
+    }
+}
+
+impl KnotHashParams {
+    /// A hash over `list_size` elements, XORed together in blocks of `block_size` for the digest,
+    /// with `suffix` appended to every input of bytes.
+    pub fn new(
+        list_size: usize,
+        rounds: usize,
+        block_size: usize,
+        suffix: Vec<usize>,
+    ) -> Result<KnotHashParams, KnotHashParamsError> {
+        if list_size == 0 || list_size > MAX_LIST_SIZE {
+            return Err(KnotHashParamsError::ListSize(list_size));
+        }
+        if block_size == 0 || !list_size.is_multiple_of(block_size) {
+            return Err(KnotHashParamsError::BlockSize {
+                block_size,
+                list_size,
+            });
+        }
+        if let Some(&length) = suffix.iter().find(|&&length| length > list_size) {
+            return Err(KnotHashParamsError::SuffixLength { length, list_size });
+        }
+
+        Ok(KnotHashParams {
+            list_size,
+            rounds,
+            block_size,
+            suffix,
+        })
+    }
+
+    /// The same hash with a different suffix.
+    pub fn with_suffix(&self, suffix: Vec<usize>) -> Result<KnotHashParams, KnotHashParamsError> {
+        KnotHashParams::new(self.list_size, self.rounds, self.block_size, suffix)
+    }
+
+    /// The number of elements in the list.
+    pub fn list_size(&self) -> usize {
+        self.list_size
+    }
+
+    /// The number of times the lengths are applied to the list.
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// The number of elements XORed together for each byte of the digest.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// The lengths appended to the input bytes before hashing.
+    pub fn suffix(&self) -> &[usize] {
+        &self.suffix
+    }
+
+    /// The number of bytes in the digest.
+    pub fn digest_len(&self) -> usize {
+        self.list_size / self.block_size
+    }
+
+    /// Hash the lengths as they are, without appending the suffix. Fails if a length is longer
+    /// than the list.
+    pub fn hash_lengths(&self, lengths: &[usize]) -> Result<KnotHash, KnotHashLengthsError> {
+        if let Some(&length) = lengths.iter().find(|&&length| length > self.list_size) {
+            return Err(KnotHashLengthsError::TooLong {
+                length,
+                list_size: self.list_size,
+            });
+        }
+
+        Ok(self.hash_of(lengths.iter().cloned()))
+    }
+
+    /// Hash the bytes as lengths, followed by the suffix. Every byte is taken modulo
+    /// `list_size + 1`, so on lists shorter than 256 elements a byte longer than the list wraps
+    /// around instead of being rejected. Bytes are never affected on the standard list.
+    pub fn hash_bytes(&self, bytes: &[u8]) -> KnotHash {
+        let wrap = self.list_size + 1;
+
+        self.hash_of(
+            bytes
+                .iter()
+                .map(|&byte| byte as usize % wrap)
+                .chain(self.suffix.iter().cloned()),
+        )
+    }
+
+    /// Every length must be no longer than the list.
+    fn hash_of<I>(&self, lengths: I) -> KnotHash
+    where
+        I: Iterator<Item = usize> + Clone,
+    {
+        KnotHasher::new(self.list_size as u32)
+            .rounds_of(self.rounds, lengths)
+            .dense_hash(self.block_size)
+    }
+}
+
+/// The state of a knot hash part way through its rounds. The list is stored inline and every
+/// length is applied in place, so no allocations are made while hashing.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct KnotHasher {
-    list: Vec<u32>,
+    list: [u8; MAX_LIST_SIZE],
+    len: usize,
     offset: usize,
     skip_size: usize,
 }
 
+impl Default for KnotHasher {
+    fn default() -> KnotHasher {
+        KnotHasher::new(MAX_LIST_SIZE as u32)
+    }
+}
+
 impl KnotHasher {
+    /// Create a hasher over the list `0, 1, .., max - 1`. Panics unless `1 <= max <= 256`.
     pub fn new(max: u32) -> KnotHasher {
+        let len = max as usize;
+        assert!(
+            len > 0 && len <= MAX_LIST_SIZE,
+            "A knot hash list must have between 1 and {} elements, not {}",
+            MAX_LIST_SIZE,
+            len
+        );
+
+        let mut list = [0; MAX_LIST_SIZE];
+        for (i, element) in list.iter_mut().enumerate().take(len) {
+            *element = i as u8;
+        }
+
         KnotHasher {
-            list: (0..max).collect(),
+            list,
+            len,
             offset: 0,
             skip_size: 0,
         }
     }
 
+    /// The current state of the list.
+    pub fn list(&self) -> &[u8] {
+        &self.list[..self.len]
+    }
+
+    /// Reverse the `length` elements starting at the current position, wrapping around the end
+    /// of the list, then move forward by `length` plus the skip size. Panics if `length` is
+    /// longer than the list.
     pub fn iterate(&mut self, length: usize) {
-        let mut new_list = self.list.clone();
-        let list_len = self.list.len();
-        for i in 0..length {
-            let idx_new = (self.offset + i) % list_len;
-            let idx_old = (self.offset + length - i - 1) % list_len;
-            new_list[idx_new] = self.list[idx_old];
+        let len = self.len;
+        assert!(length <= len, "Length {} is longer than the list", length);
+        let end = self.offset + length;
+
+        if end <= len {
+            self.list[self.offset..end].reverse();
+        } else {
+            // The section wraps around the end of the list, so swap pairs working inwards from
+            // both ends, wrapping each index as it crosses the boundary.
+            let list = &mut self.list[..len];
+            let mut front = self.offset;
+            let mut back = end - len;
+            for _ in 0..length / 2 {
+                back = if back == 0 { len - 1 } else { back - 1 };
+                list.swap(front, back);
+                front = if front == len - 1 { 0 } else { front + 1 };
+            }
         }
 
-        self.list = new_list;
-        self.offset = (self.offset + length + self.skip_size) % list_len;
+        self.offset = (self.offset + length + self.skip_size) % len;
         self.skip_size += 1;
     }
 
@@ -34,89 +272,208 @@ impl KnotHasher {
         for length in lengths.iter() {
             self.iterate(*length);
         }
-        self.list[0] * self.list[1]
+        self.list[0] as u32 * self.list[1] as u32
+    }
+
+    pub fn all_rounds(self, lengths: &[usize]) -> Self {
+        self.rounds_of(STANDARD_ROUNDS, lengths.iter().cloned())
     }
 
-    pub fn all_rounds(mut self, lengths: &[usize]) -> Self {
-        for _ in 0..64 {
-            for length in lengths.iter() {
-                self.iterate(*length);
+    fn rounds_of<I>(mut self, rounds: usize, lengths: I) -> Self
+    where
+        I: Iterator<Item = usize> + Clone,
+    {
+        for _ in 0..rounds {
+            for length in lengths.clone() {
+                self.iterate(length);
             }
         }
         self
     }
 
-    pub fn sparse_hash(lengths: &[usize]) -> Vec<u32> {
-        KnotHasher::new(256).all_rounds(lengths).list
+    pub fn sparse_hash(lengths: &[usize]) -> [u8; MAX_LIST_SIZE] {
+        KnotHasher::default().all_rounds(lengths).list
     }
 
-    pub fn hash(lengths: &[usize]) -> KnotHash {
-        let mut dense_hash_bytes = [0; 16];
-        let sparse_hash = KnotHasher::sparse_hash(lengths);
-        let mut blocks = sparse_hash.chunks(16).map(|block| {
-            block[1..].iter().fold(block[0], |acc, &val| acc ^ val)
-        });
+    /// XOR together each block of `block_size` elements of the list.
+    fn dense_hash(&self, block_size: usize) -> KnotHash {
+        let mut bytes = [0; MAX_LIST_SIZE];
+        let blocks = self.list().chunks(block_size);
+        let len = blocks.len();
 
-        for i in 0..16 {
-            dense_hash_bytes[i] = blocks.next().unwrap() as u8;
+        for (byte, block) in bytes.iter_mut().zip(blocks) {
+            *byte = block.iter().fold(0, |acc, &val| acc ^ val);
         }
 
-        KnotHash { bytes: dense_hash_bytes }
+        KnotHash { bytes, len }
+    }
+
+    /// The standard knot hash of the lengths, without appending the suffix. Fails if a length is
+    /// longer than 256.
+    pub fn hash(lengths: &[usize]) -> Result<KnotHash, KnotHashLengthsError> {
+        KnotHashParams::default().hash_lengths(lengths)
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// The digest of a knot hash. The standard knot hash has a 16 byte digest, but hashes with other
+/// `KnotHashParams` may have anything up to 256 bytes.
+///
+/// Hashes are ordered by comparing their digests byte by byte.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct KnotHash {
-    bytes: [u8; 16],
+    // Bytes past `len` are always zero.
+    bytes: [u8; MAX_LIST_SIZE],
+    len: usize,
 }
 
 impl KnotHash {
     pub fn hash_str(input: &str) -> KnotHash {
-        let lengths = {
-            let mut input_vec: Vec<_> = input
-                .trim()
-                .as_bytes()
-                .iter()
-                .map(|&byte| byte as usize)
-                .collect();
-            input_vec.append(&mut vec![17, 31, 73, 47, 23]);
-            input_vec
-        };
-
-        KnotHasher::hash(&lengths)
+        KnotHash::hash_str_with(input, &KnotHashParams::default())
     }
 
+    pub fn hash_str_with(input: &str, params: &KnotHashParams) -> KnotHash {
+        params.hash_bytes(input.trim().as_bytes())
+    }
 
-    pub fn hash_list_of_lengths(input: &str) -> KnotHash {
-        let lengths: Vec<_> = input
+    /// Hash a comma separated list of lengths with `KnotHasher::hash`.
+    pub fn hash_list_of_lengths(input: &str) -> Result<KnotHash, KnotHashLengthsError> {
+        let lengths = input
             .trim()
             .split(",")
-            .map(|num_str| num_str.parse().unwrap())
-            .collect();
+            .map(|num_str| {
+                num_str
+                    .trim()
+                    .parse()
+                    .map_err(|_| KnotHashLengthsError::Invalid(num_str.to_owned()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
         KnotHasher::hash(&lengths)
     }
 
+    /// The number of bytes in the digest.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    /// The bits of the digest, starting from the most significant bit of the first byte.
+    pub fn bits(&self) -> impl Iterator<Item = bool> + '_ {
+        self.as_bytes()
+            .iter()
+            .flat_map(|&byte| (0..8).rev().map(move |bit| byte & (1 << bit) != 0))
+    }
+
+    /// The number of bits that differ between two digests. Panics if the digests are of different
+    /// lengths.
+    pub fn hamming_distance(&self, other: &KnotHash) -> u32 {
+        assert_eq!(
+            self.len, other.len,
+            "Cannot compare digests of different lengths"
+        );
+
+        self.as_bytes()
+            .iter()
+            .zip(other.as_bytes().iter())
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum()
+    }
+
     pub fn to_str(&self) -> String {
-        format!(
-            "{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
-            self.bytes[0],
-            self.bytes[1],
-            self.bytes[2],
-            self.bytes[3],
-            self.bytes[4],
-            self.bytes[5],
-            self.bytes[6],
-            self.bytes[7],
-            self.bytes[8],
-            self.bytes[9],
-            self.bytes[10],
-            self.bytes[11],
-            self.bytes[12],
-            self.bytes[13],
-            self.bytes[14],
-            self.bytes[15]
-        )
+        self.to_string()
+    }
+}
+
+impl fmt::Display for KnotHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for KnotHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        for byte in self.as_bytes() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::UpperHex for KnotHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        for byte in self.as_bytes() {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for KnotHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "KnotHash({})", self)
+    }
+}
+
+/// A string that is not the hex digest of a `KnotHash`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseKnotHashError {
+    /// The digest must have an even number of hex digits, between 2 and 512.
+    InvalidLength(usize),
+    /// The character at the given index is not a hex digit.
+    InvalidDigit { index: usize, c: char },
+}
+
+impl fmt::Display for ParseKnotHashError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseKnotHashError::InvalidLength(len) => write!(
+                f,
+                "a knot hash digest must have an even number of hex digits, between 2 and {}, \
+                 not {}",
+                2 * MAX_LIST_SIZE,
+                len
+            ),
+            ParseKnotHashError::InvalidDigit { index, c } => {
+                write!(f, "invalid hex digit {:?} at index {}", c, index)
+            }
+        }
+    }
+}
+
+impl FromStr for KnotHash {
+    type Err = ParseKnotHashError;
+
+    /// Parse a hex digest, such as the 32 hex digits of a standard knot hash, in either case.
+    fn from_str(input: &str) -> Result<KnotHash, ParseKnotHashError> {
+        let digits = input.chars().count();
+        if digits == 0 || !digits.is_multiple_of(2) || digits > 2 * MAX_LIST_SIZE {
+            return Err(ParseKnotHashError::InvalidLength(digits));
+        }
+
+        let mut bytes = [0; MAX_LIST_SIZE];
+        for (index, c) in input.chars().enumerate() {
+            let nibble = c.to_digit(16)
+                .ok_or(ParseKnotHashError::InvalidDigit { index, c })?;
+            bytes[index / 2] = (bytes[index / 2] << 4) | nibble as u8;
+        }
+
+        Ok(KnotHash {
+            bytes,
+            len: digits / 2,
+        })
     }
 }
 
@@ -126,7 +483,7 @@ impl PartialEq<str> for KnotHash {
     }
 }
 
-impl<'a> PartialEq<KnotHash> for &'a str {
+impl PartialEq<KnotHash> for &str {
     fn eq(&self, other: &KnotHash) -> bool {
         other.to_str() == *self
     }
@@ -136,10 +493,72 @@ impl Index<u8> for KnotHash {
     type Output = u8;
 
     fn index(&self, index: u8) -> &u8 {
-        self.bytes.index(index as usize)
+        self.bytes[..self.len].index(index as usize)
+    }
+}
+
+/// Builds a knot hash from bytes fed in any number of pieces.
+///
+/// Every round of the knot hash replays the complete input, so the bytes are buffered until the
+/// hash is finalized. Unlike `KnotHash::hash_str` the input is not trimmed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KnotHashBuilder {
+    bytes: Vec<u8>,
+    params: KnotHashParams,
+}
+
+impl KnotHashBuilder {
+    pub fn new() -> KnotHashBuilder {
+        KnotHashBuilder::default()
+    }
+
+    pub fn with_params(params: KnotHashParams) -> KnotHashBuilder {
+        KnotHashBuilder {
+            bytes: vec![],
+            params,
+        }
+    }
+
+    /// Append bytes to the input.
+    pub fn update(&mut self, bytes: &[u8]) -> &mut KnotHashBuilder {
+        self.bytes.extend_from_slice(bytes);
+        self
+    }
+
+    /// Calculate the knot hash of all the bytes fed in so far.
+    pub fn finalize(&self) -> KnotHash {
+        self.params.hash_bytes(&self.bytes)
     }
 }
 
+impl Hasher for KnotHashBuilder {
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    /// The first eight bytes of the knot hash, or the whole digest if it is shorter, read as a
+    /// big-endian number.
+    fn finish(&self) -> u64 {
+        self.finalize()
+            .as_bytes()
+            .iter()
+            .take(8)
+            .fold(0, |acc, &byte| (acc << 8) | byte as u64)
+    }
+}
+
+/// Creates `KnotHashBuilder`s, so that knot hashes can be used as the hasher of a `HashMap` or
+/// `HashSet`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BuildKnotHasher;
+
+impl BuildHasher for BuildKnotHasher {
+    type Hasher = KnotHashBuilder;
+
+    fn build_hasher(&self) -> KnotHashBuilder {
+        KnotHashBuilder::new()
+    }
+}
 
 #[cfg(test)]
 mod test {
@@ -150,16 +569,16 @@ mod test {
         let mut hash = KnotHasher::new(5);
 
         hash.iterate(3);
-        assert_eq!(vec![2, 1, 0, 3, 4], hash.list);
+        assert_eq!([2, 1, 0, 3, 4], hash.list());
 
         hash.iterate(4);
-        assert_eq!(vec![4, 3, 0, 1, 2], hash.list);
+        assert_eq!([4, 3, 0, 1, 2], hash.list());
 
         hash.iterate(1);
-        assert_eq!(vec![4, 3, 0, 1, 2], hash.list);
+        assert_eq!([4, 3, 0, 1, 2], hash.list());
 
         hash.iterate(5);
-        assert_eq!(vec![3, 4, 2, 1, 0], hash.list);
+        assert_eq!([3, 4, 2, 1, 0], hash.list());
     }
 
     #[test]
@@ -184,4 +603,263 @@ mod test {
             KnotHash::hash_str("1,2,4")
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn builder_matches_hash_str() {
+        assert_eq!(
+            KnotHash::hash_str("AoC 2017"),
+            KnotHashBuilder::new().update(b"AoC 2017").finalize()
+        );
+        assert_eq!(
+            KnotHash::hash_str("AoC 2017"),
+            KnotHashBuilder::new()
+                .update(b"AoC")
+                .update(b"")
+                .update(b" 2017")
+                .finalize()
+        );
+        assert_eq!(KnotHash::hash_str(""), KnotHashBuilder::new().finalize());
+    }
+
+    #[test]
+    fn builder_does_not_trim() {
+        assert_ne!(
+            KnotHash::hash_str("1,2,3"),
+            KnotHashBuilder::new().update(b"1,2,3\n").finalize()
+        );
+    }
+
+    #[test]
+    fn hasher_finish() {
+        let mut hasher = BuildKnotHasher.build_hasher();
+        hasher.write(b"AoC 2017");
+
+        // The first eight bytes of 33efeb34ea91902bb2f59c9920caa6cd
+        assert_eq!(0x33efeb34ea91902b, hasher.finish());
+        assert_eq!(0x33efeb34ea91902b, hasher.finish());
+    }
+
+    #[test]
+    fn hasher_finish_short_digest() {
+        let params = KnotHashParams::new(256, 64, 64, STANDARD_SUFFIX.to_vec()).unwrap();
+        let mut hasher = KnotHashBuilder::with_params(params.clone());
+        hasher.write(b"AoC 2017");
+
+        let digest = KnotHash::hash_str_with("AoC 2017", &params);
+        let expected = digest
+            .as_bytes()
+            .iter()
+            .fold(0, |acc, &byte| (acc << 8) | byte as u64);
+        assert_eq!(4, digest.len());
+        assert_eq!(expected, hasher.finish());
+    }
+
+    #[test]
+    fn hash_map_with_knot_hasher() {
+        use std::collections::HashMap;
+
+        let mut map = HashMap::with_hasher(BuildKnotHasher);
+        map.insert("flqrgnkx", 1);
+        map.insert("AoC 2017", 2);
+
+        assert_eq!(Some(&1), map.get("flqrgnkx"));
+        assert_eq!(Some(&2), map.get("AoC 2017"));
+        assert_eq!(None, map.get("1,2,3"));
+    }
+
+    #[test]
+    fn default_params() {
+        let params = KnotHashParams::default();
+
+        assert_eq!(16, params.digest_len());
+        assert_eq!(KnotHash::hash_str("AoC 2017"), KnotHash::hash_str_with("AoC 2017", &params));
+        assert_eq!(
+            KnotHash::hash_list_of_lengths("3,4,1,5"),
+            params.hash_lengths(&[3, 4, 1, 5])
+        );
+    }
+
+    #[test]
+    fn small_list_params() {
+        // A single round over [0, 1, 2, 3, 4] gives [3, 4, 2, 1, 0].
+        let params = KnotHashParams::new(5, 1, 1, vec![]).unwrap();
+        assert_eq!("0304020100", params.hash_lengths(&[3, 4, 1, 5]).unwrap());
+
+        let params = KnotHashParams::new(5, 1, 5, vec![]).unwrap();
+        assert_eq!("04", params.hash_lengths(&[3, 4, 1, 5]).unwrap());
+    }
+
+    #[test]
+    fn variable_length_digest() {
+        let params = KnotHashParams::new(256, 64, 8, STANDARD_SUFFIX.to_vec()).unwrap();
+        let hash = KnotHash::hash_str_with("AoC 2017", &params);
+
+        assert_eq!(32, hash.len());
+        assert_eq!(64, hash.to_str().len());
+        assert_ne!(KnotHash::hash_str("AoC 2017"), hash);
+    }
+
+    #[test]
+    fn builder_with_params() {
+        let params = KnotHashParams::new(256, 16, 16, vec![1, 2, 3]).unwrap();
+        assert_eq!(params, params.with_suffix(vec![1, 2, 3]).unwrap());
+
+        assert_eq!(
+            KnotHash::hash_str_with("flqrgnkx-0", &params),
+            KnotHashBuilder::with_params(params)
+                .update(b"flqrgnkx")
+                .update(b"-0")
+                .finalize()
+        );
+    }
+
+    #[test]
+    fn invalid_params() {
+        assert_eq!(
+            Err(KnotHashParamsError::ListSize(0)),
+            KnotHashParams::new(0, 1, 1, vec![])
+        );
+        assert_eq!(
+            Err(KnotHashParamsError::ListSize(257)),
+            KnotHashParams::new(257, 1, 1, vec![])
+        );
+        assert_eq!(
+            Err(KnotHashParamsError::BlockSize {
+                block_size: 10,
+                list_size: 256,
+            }),
+            KnotHashParams::new(256, 64, 10, vec![])
+        );
+        assert_eq!(
+            Err(KnotHashParamsError::BlockSize {
+                block_size: 0,
+                list_size: 256,
+            }),
+            KnotHashParams::new(256, 64, 0, vec![])
+        );
+        assert_eq!(
+            Err(KnotHashParamsError::SuffixLength {
+                length: 73,
+                list_size: 64,
+            }),
+            KnotHashParams::new(64, 64, 16, STANDARD_SUFFIX.to_vec())
+        );
+    }
+
+    #[test]
+    fn long_lengths_are_rejected() {
+        let params = KnotHashParams::new(5, 1, 1, vec![]).unwrap();
+        assert_eq!(
+            Err(KnotHashLengthsError::TooLong {
+                length: 9,
+                list_size: 5,
+            }),
+            params.hash_lengths(&[3, 4, 1, 5, 9])
+        );
+
+        assert_eq!(
+            Err(KnotHashLengthsError::TooLong {
+                length: 300,
+                list_size: 256,
+            }),
+            KnotHash::hash_list_of_lengths("300")
+        );
+        assert!(KnotHash::hash_list_of_lengths("256").is_ok());
+        assert_eq!(
+            Err(KnotHashLengthsError::Invalid("x".to_owned())),
+            KnotHash::hash_list_of_lengths("1,x")
+        );
+    }
+
+    #[test]
+    fn bytes_wrap_on_small_lists() {
+        let params = KnotHashParams::new(5, 1, 1, vec![]).unwrap();
+
+        assert_eq!(
+            params.hash_lengths(&[b'A' as usize % 6, b'z' as usize % 6]),
+            Ok(params.hash_bytes(b"Az"))
+        );
+    }
+
+    #[test]
+    fn hex_formatting() {
+        let hash = KnotHash::hash_str("AoC 2017");
+
+        assert_eq!("33efeb34ea91902bb2f59c9920caa6cd", hash.to_string());
+        assert_eq!("33efeb34ea91902bb2f59c9920caa6cd", format!("{:x}", hash));
+        assert_eq!("33EFEB34EA91902BB2F59C9920CAA6CD", format!("{:X}", hash));
+        assert_eq!("0x33efeb34ea91902bb2f59c9920caa6cd", format!("{:#x}", hash));
+    }
+
+    #[test]
+    fn parse_hex_digest() {
+        let hash = KnotHash::hash_str("AoC 2017");
+
+        assert_eq!(Ok(hash), "33efeb34ea91902bb2f59c9920caa6cd".parse());
+        assert_eq!(Ok(hash), "33EFEB34EA91902BB2F59C9920CAA6CD".parse());
+        assert_eq!(
+            &[0x33, 0xef, 0xeb, 0x34],
+            &"33efeb34".parse::<KnotHash>().unwrap().as_bytes()
+        );
+    }
+
+    #[test]
+    fn parse_hex_digest_errors() {
+        assert_eq!(
+            Err(ParseKnotHashError::InvalidLength(0)),
+            "".parse::<KnotHash>()
+        );
+        assert_eq!(
+            Err(ParseKnotHashError::InvalidLength(31)),
+            "33efeb34ea91902bb2f59c9920caa6c".parse::<KnotHash>()
+        );
+        assert_eq!(
+            Err(ParseKnotHashError::InvalidDigit { index: 3, c: 'g' }),
+            "33egeb34ea91902bb2f59c9920caa6cd".parse::<KnotHash>()
+        );
+    }
+
+    #[test]
+    fn bits_are_msb_first() {
+        let hash: KnotHash = "a0c2".parse().unwrap();
+        let bits: String = hash.bits().map(|bit| if bit { '1' } else { '0' }).collect();
+
+        assert_eq!("1010000011000010", bits);
+        assert_eq!(128, KnotHash::hash_str("").bits().count());
+    }
+
+    #[test]
+    fn hamming_distance() {
+        let a: KnotHash = "ff00".parse().unwrap();
+        let b: KnotHash = "0f01".parse().unwrap();
+
+        assert_eq!(0, a.hamming_distance(&a));
+        assert_eq!(5, a.hamming_distance(&b));
+        assert_eq!(5, b.hamming_distance(&a));
+    }
+
+    #[test]
+    fn hashes_as_keys() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let hashes = ["1,2,3", "AoC 2017", "", "1,2,3"]
+            .iter()
+            .map(|input| KnotHash::hash_str(input))
+            .collect::<Vec<_>>();
+
+        assert_eq!(3, hashes.iter().collect::<HashSet<_>>().len());
+        assert_eq!(
+            vec![
+                "33efeb34ea91902bb2f59c9920caa6cd",
+                "3efbe78a8d82f29979031a4aa0b16a9d",
+                "a2582a3a0e66e6e86e3812dcb672a272",
+            ],
+            hashes
+                .iter()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .map(|hash| hash.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+}
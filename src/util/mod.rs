@@ -1,5 +1,7 @@
 //! A collection of utility functions used across the advent calendar
 
+pub mod cycle;
+pub mod grid;
 pub mod knot_hash;
 
 use std::fs::File;
@@ -19,14 +21,70 @@ pub fn file_as_string<P: AsRef<Path>>(path: P) -> String {
 }
 
 
-named!(pub parse_usize(&str) -> usize,
-    map!(digit, |s| FromStr::from_str(s).unwrap())
+named!(signed_digits(&str) -> &str,
+    recognize!(pair!(opt!(alt!(tag_s!("-") | tag_s!("+"))), digit))
 );
 
-named!(pub parse_isize(&str) -> isize,
-    map!(digit, |s| FromStr::from_str(s).unwrap())
+named!(pub parse_usize(&str) -> usize,
+    map_res!(digit, FromStr::from_str)
 );
 
 named!(pub parse_u64(&str) -> u64,
-    map!(digit, |s| FromStr::from_str(s).unwrap())
+    map_res!(digit, FromStr::from_str)
+);
+
+named_attr!(
+    #[doc = "Parse an optionally signed integer. Values which do not fit in an `i32` are a \
+             parse error."],
+    pub parse_i32(&str) -> i32,
+    map_res!(signed_digits, FromStr::from_str)
 );
+
+named_attr!(
+    #[doc = "Parse an optionally signed integer. Values which do not fit in an `i64` are a \
+             parse error."],
+    pub parse_i64(&str) -> i64,
+    map_res!(signed_digits, FromStr::from_str)
+);
+
+named_attr!(
+    #[doc = "Parse an optionally signed integer. Values which do not fit in an `isize` are a \
+             parse error."],
+    pub parse_isize(&str) -> isize,
+    map_res!(signed_digits, FromStr::from_str)
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{ErrorKind, IResult};
+
+    #[test]
+    fn parse_signed() {
+        assert_eq!(IResult::Done("", 12), parse_i32("12"));
+        assert_eq!(IResult::Done("", -12), parse_i32("-12"));
+        assert_eq!(IResult::Done("", 12), parse_i32("+12"));
+        assert_eq!(IResult::Done(" ", -7), parse_i64("-7 "));
+        assert_eq!(IResult::Done(",3", -1), parse_isize("-1,3"));
+    }
+
+    #[test]
+    fn parse_signed_rejects_bare_sign() {
+        assert!(parse_i32("-").is_incomplete());
+        assert!(parse_i32("- 5").is_err());
+        assert!(parse_i32("a").is_err());
+    }
+
+    #[test]
+    fn parse_overflow() {
+        assert_eq!(IResult::Done("", i32::MIN), parse_i32("-2147483648"));
+        assert_eq!(
+            IResult::Error(ErrorKind::MapRes),
+            parse_i32("2147483648")
+        );
+        assert_eq!(
+            IResult::Error(ErrorKind::MapRes),
+            parse_u64("18446744073709551616")
+        );
+    }
+}